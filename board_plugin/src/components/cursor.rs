@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+// 键盘操作时标记当前选中tile的光标
+#[derive(Component, Debug, Copy, Clone)]
+pub struct TileCursor;
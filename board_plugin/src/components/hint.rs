@@ -0,0 +1,7 @@
+use bevy::prelude::{Color, Component};
+
+// 被提示高亮的sprite，记录原来的颜色以便清除提示时恢复
+#[derive(Component, Debug, Copy, Clone)]
+pub struct HintHighlight {
+    pub original: Color,
+}
@@ -0,0 +1,17 @@
+use bevy::prelude::Component;
+
+// HUD的根节点
+#[derive(Component, Debug, Copy, Clone)]
+pub struct HudRoot;
+
+// 剩余雷数显示
+#[derive(Component, Debug, Copy, Clone)]
+pub struct MineCounter;
+
+// 游戏用时显示
+#[derive(Component, Debug, Copy, Clone)]
+pub struct GameClock;
+
+// 重新开始按钮，按钮上的表情反映游戏状态
+#[derive(Component, Debug, Copy, Clone)]
+pub struct ResetButton;
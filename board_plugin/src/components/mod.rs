@@ -1,8 +1,10 @@
 use bevy::prelude::Component;
 
-pub mod coordinates;
+pub mod cursor;
+pub mod hint;
+pub mod hud;
 
-pub use coordinates::Coordinates;
+pub use minesweeper_core::Coordinates;
 
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
 #[derive(Component, Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::components::Coordinates;
+use crate::resources::BoardOptionsError;
+
+// 点击某个tile后触发的翻开事件
+#[derive(Debug, Copy, Clone)]
+pub struct TileTriggerEvent(pub Coordinates);
+
+// 右键点击某个tile后触发的标记事件
+#[derive(Debug, Copy, Clone)]
+pub struct TileMarkEvent(pub Coordinates);
+
+// 在已翻开的数字块上双击，周围插旗数等于数字时翻开其余的相邻块
+#[derive(Debug, Copy, Clone)]
+pub struct TileChordEvent(pub Coordinates);
+
+// 所有不是炸弹的tile都被翻开时触发，hints为这局使用提示的次数
+// assisted表示使用过撤销，不应计入记录
+#[derive(Debug, Copy, Clone)]
+pub struct GameWon {
+    pub hints: u32,
+    pub assisted: bool,
+}
+
+// 翻开炸弹时触发，at为被翻开的炸弹的坐标
+#[derive(Debug, Copy, Clone)]
+pub struct GameLost {
+    pub at: Coordinates,
+    pub hints: u32,
+    pub assisted: bool,
+}
+
+// 撤销最近的一次操作，使用过撤销的游戏会被标记为辅助完成
+#[derive(Debug, Copy, Clone)]
+pub struct Undo;
+
+// 重做最近一次被撤销的操作
+#[derive(Debug, Copy, Clone)]
+pub struct Redo;
+
+// 请求提示：高亮一个可以翻开的tile
+#[derive(Debug, Copy, Clone)]
+pub struct HintEvent;
+
+// 重新开始游戏，replay为true时沿用当前局的炸弹布局
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NewGame {
+    pub replay: bool,
+}
+
+// 无法创建board时触发，此时不会生成board，修改设置后可以通过NewGame重试
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    InvalidOptions(BoardOptionsError),
+    NoPrimaryWindow,
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidOptions(error) => write!(f, "invalid board options: {}", error),
+            Self::NoPrimaryWindow => write!(f, "no primary window"),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
@@ -1,161 +1,557 @@
 pub mod bounds;
 pub mod components;
+pub mod events;
 pub mod resources;
 pub mod systems;
 
+use bevy::ecs::schedule::{ShouldRun, StateData};
+use bevy::ecs::system::EntityCommands;
 use bevy::log;
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use bevy::utils::{Duration, HashMap};
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::RegisterInspectable;
-use resources::tile::Tile;
+use minesweeper_core::{Game, Tile, TileState};
+use resources::BoardOptions;
 use resources::BoardPosition;
 use resources::TileSize;
-use resources::{tile_map::TileMap, BoardOptions};
 
 use crate::bounds::Bounds2;
-use crate::components::{Bomb, BombNeighbor, Coordinates, Uncover};
+#[cfg(feature = "debug")]
+use crate::components::Uncover;
+use crate::components::{Bomb, BombNeighbor, Coordinates};
+use crate::events::{
+    BoardError, GameLost, GameWon, HintEvent, NewGame, Redo, TileChordEvent, TileMarkEvent,
+    TileTriggerEvent, Undo,
+};
 use crate::resources::board::Board;
-use crate::systems::input::event_handle;
+use crate::resources::replay::{Replay, ReplayPlayback, ReplayRecorder};
+use crate::resources::save::SavedGame;
+use crate::systems::camera::{pan_camera, zoom_camera};
+use crate::systems::hint::{clear_hint, hint_input, show_hint};
+use crate::systems::history::{history_handler, history_input};
+use crate::systems::hud::{
+    despawn_hud, reset_button, spawn_hud, tick_clock, update_clock, update_face,
+    update_mine_counter, HUD_HEIGHT,
+};
+use crate::systems::input::{event_handle, new_game_input};
+use crate::systems::keyboard::keyboard_input;
+use crate::systems::layout::resize_board;
+use crate::systems::mark::mark_tiles;
+use crate::systems::replay::{play_replay, record_inputs, save_replay};
+use crate::systems::save::autosave_on_exit;
+use crate::systems::uncover::{
+    chord_event_handler, reveal_on_loss, trigger_event_handler, uncover_tiles,
+};
+#[cfg(feature = "debug")]
+use crate::{
+    resources::ProbabilityOverlay,
+    systems::probability::{probability_overlay, toggle_probability_overlay},
+};
+
+// running_state为宿主程序提供的状态，进入该状态时创建board，离开时销毁board
+// 只有处于该状态时才会处理输入
+pub struct BoardPlugin<T> {
+    pub running_state: T,
+}
 
-pub struct BoardPlugin;
+// 处于running_state的运行条件，依赖board的系统在此基础上还要求board已经创建
+#[derive(Debug, Clone, PartialEq, Eq, Hash, RunCriteriaLabel)]
+struct RunningState;
 
-impl Plugin for BoardPlugin {
+impl<T: StateData> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
         #[cfg(feature = "debug")]
         // registering custom component to be able to edit it in inspector
         {
-            app.register_inspectable::<Coordinates>();
             app.register_inspectable::<BombNeighbor>();
             app.register_inspectable::<Bomb>();
             app.register_inspectable::<Uncover>();
         }
-        app.add_startup_system(BoardPlugin::create_board)
-            .add_system(event_handle);
+        // 调试时可以用P键显示每个tile是炸弹的概率
+        #[cfg(feature = "debug")]
+        {
+            app.init_resource::<ProbabilityOverlay>();
+            app.add_system_set(
+                SystemSet::new()
+                    .with_run_criteria(RunCriteria::pipe(RunningState, board_exists))
+                    .with_system(toggle_probability_overlay)
+                    .with_system(
+                        probability_overlay
+                            .after(trigger_event_handler)
+                            .after(chord_event_handler)
+                            .after(mark_tiles),
+                    ),
+            );
+        }
+        app.add_system_set(
+            SystemSet::on_enter(self.running_state.clone())
+                .with_system(Self::create_board)
+                .with_system(spawn_hud),
+        )
+        // 创建board失败时只运行不依赖board的系统，NewGame可以重新尝试创建
+        .add_system_set(
+            SystemSet::new()
+                .with_run_criteria(
+                    State::<T>::on_update(self.running_state.clone()).label(RunningState),
+                )
+                .with_system(new_game_input)
+                .with_system(hint_input)
+                .with_system(history_input)
+                .with_system(
+                    Self::new_game
+                        .after(trigger_event_handler)
+                        .after(chord_event_handler)
+                        .after(uncover_tiles)
+                        .after(mark_tiles)
+                        .after(reveal_on_loss)
+                        .after(show_hint)
+                        .after(history_handler),
+                ),
+        )
+        .add_system_set(
+            SystemSet::new()
+                .with_run_criteria(RunCriteria::pipe(RunningState, board_exists))
+                .with_system(event_handle)
+                .with_system(keyboard_input.before(clear_hint))
+                // 同一帧内的输入按翻开、双击、插旗、撤销/重做、提示的顺序处理，录像按相同的顺序记录
+                .with_system(trigger_event_handler)
+                .with_system(chord_event_handler.after(trigger_event_handler))
+                .with_system(uncover_tiles)
+                .with_system(mark_tiles.after(chord_event_handler))
+                .with_system(reveal_on_loss)
+                .with_system(history_handler.after(mark_tiles))
+                .with_system(play_replay)
+                .with_system(record_inputs)
+                .with_system(save_replay)
+                .with_system(
+                    clear_hint
+                        .before(show_hint)
+                        .before(trigger_event_handler)
+                        .before(chord_event_handler)
+                        .before(mark_tiles),
+                )
+                .with_system(show_hint.after(history_handler))
+                .with_system(tick_clock)
+                .with_system(update_mine_counter)
+                .with_system(update_clock)
+                .with_system(update_face)
+                .with_system(reset_button)
+                .with_system(zoom_camera.before(pan_camera))
+                .with_system(pan_camera),
+        )
+        .add_system_set(
+            SystemSet::on_exit(self.running_state.clone())
+                .with_system(Self::cleanup_board)
+                .with_system(despawn_hud),
+        )
+        .add_event::<TileTriggerEvent>()
+        .add_event::<TileMarkEvent>()
+        .add_event::<TileChordEvent>()
+        .add_event::<GameWon>()
+        .add_event::<GameLost>()
+        .add_event::<NewGame>()
+        .add_event::<HintEvent>()
+        .add_event::<Undo>()
+        .add_event::<Redo>()
+        .add_event::<BoardError>()
+        // 暂停时也可能退出或者改变窗口大小，这两个系统不限定状态
+        .add_system(autosave_on_exit)
+        .add_system(resize_board.before(event_handle));
         log::info!("Loaded Board Plugin");
     }
 }
 
-impl BoardPlugin {
+impl<T> BoardPlugin<T> {
+    // 存在ReplayPlayback时使用录像的布局
+    // 开启自动保存时，第一次创建board会继续上次退出时保存的游戏，并恢复存档中的设置
+    // 设置无效或者没有窗口时不创建board，记录错误并发送BoardError
     pub fn create_board(
         mut commands: Commands,
-        board_options: Res<BoardOptions>,
+        mut board_options: ResMut<BoardOptions>,
         windows: Res<Windows>,
         assets_server: Res<AssetServer>,
+        playback: Option<Res<ReplayPlayback>>,
+        mut board_error_ewr: EventWriter<BoardError>,
+        mut started: Local<bool>,
     ) {
-        let map_size = board_options.map_size;
-        let mut tile_map = TileMap::new(map_size.0, map_size.1);
-        let window = windows.get_primary().unwrap();
-        let font = assets_server.load("fonts/pixeled.ttf");
-        let bomb_png = assets_server.load("sprites/bomb.png");
-        // 设定炸弹数目
-        tile_map.set_bombs(board_options.bomb_count);
+        let saved = (!*started && board_options.autosave && playback.is_none())
+            .then(load_autosave)
+            .flatten();
+        if let Some(saved) = saved.as_ref() {
+            *board_options = saved.options.clone();
+        }
+        let window = match board_window(&board_options, &windows) {
+            Ok(window) => window,
+            Err(error) => {
+                log::error!("Cannot create board: {}", error);
+                board_error_ewr.send(error);
+                return;
+            }
+        };
+        *started = true;
+        let (game, elapsed, replay) = match (playback, saved) {
+            (None, Some(saved)) => (saved.game, saved.elapsed, saved.replay),
+            (playback, _) => {
+                let game = match playback {
+                    Some(playback) => playback.replay.game(),
+                    None => game_from_options(&board_options),
+                };
+                let replay = Replay::new(board_options.clone(), game.seed());
+                (game, Duration::ZERO, Some(replay))
+            }
+        };
+        spawn_board(
+            &mut commands,
+            &board_options,
+            window,
+            &assets_server,
+            game,
+            elapsed,
+            replay,
+        );
+    }
 
-        #[cfg(feature = "debug")]
-        log::info!("{}", tile_map.console_output());
+    // 重新开始游戏：销毁当前的board，根据当前的BoardOptions重新生成
+    // replay为true时沿用当前的炸弹布局
+    // 新的设置无效时保留当前的board；之前创建失败时没有board，直接重新创建
+    pub fn new_game(
+        mut commands: Commands,
+        board: Option<Res<Board>>,
+        board_options: Res<BoardOptions>,
+        windows: Res<Windows>,
+        assets_server: Res<AssetServer>,
+        mut new_game_evr: EventReader<NewGame>,
+        mut board_error_ewr: EventWriter<BoardError>,
+    ) {
+        let Some(event) = new_game_evr.iter().last() else {
+            return;
+        };
+        let window = match board_window(&board_options, &windows) {
+            Ok(window) => window,
+            Err(error) => {
+                log::error!("Cannot start a new game: {}", error);
+                board_error_ewr.send(error);
+                return;
+            }
+        };
+        let current = board.as_ref().map(|board| &board.game);
+        let replay = event.replay
+            && current.is_some_and(|game| {
+                game.mines_placed() && (game.width(), game.height()) == board_options.map_size
+            });
+        log::info!("Starting a new game (replay: {})", replay);
+        let game = match current {
+            Some(game) if replay => Game::from_tile_map(game.tile_map().clone()),
+            _ => game_from_options(&board_options),
+        };
 
-        let tile_size = actual_tile_size(
-            (window.width(), window.height()),
-            &board_options.tile_size,
-            map_size,
+        // 开始新的一局时停止回放
+        commands.remove_resource::<ReplayPlayback>();
+        if let Some(board) = board {
+            commands.entity(board.entity).despawn_recursive();
+        }
+        let replay = Replay::new(board_options.clone(), game.seed());
+        spawn_board(
+            &mut commands,
+            &board_options,
+            window,
+            &assets_server,
+            game,
+            Duration::ZERO,
+            Some(replay),
         );
+    }
 
-        #[cfg(feature = "debug")]
-        log::info!("tile size is {}", tile_size);
-
-        // 计算board的中心位置
-        let (board_width, board_height) =
-            (tile_size * map_size.0 as f32, tile_size * map_size.1 as f32);
-        let board_position = board_position((board_width, board_height), board_options.position);
-
-        // 创建board
-        commands
-            .spawn_empty()
-            .insert(Name::new("Board"))
-            .insert(Transform::from_translation(board_position))
-            .insert(GlobalTransform::default())
-            .insert(ComputedVisibility::default())
-            .insert(Visibility { is_visible: true }) // 两个Visibility用来将父级的空元素显示，否则所有子元素都不能显示
-            .with_children(|parent| {
-                parent
-                    .spawn(SpriteBundle {
-                        sprite: Sprite {
-                            color: Color::WHITE,
-                            custom_size: Some(Vec2::new(board_width, board_height)),
-                            ..default()
-                        },
-                        transform: Transform::from_xyz(board_width / 2., board_height / 2., 0.),
-                        ..default()
-                    })
-                    .insert(Name::new("Background"));
-            })
-            .with_children(|parent| {
-                // 创建tile
-                sapwn_tiles(
-                    parent,
-                    &tile_map,
-                    tile_size,
-                    board_options.tile_padding,
-                    font,
-                    bomb_png,
-                );
-            });
+    // 销毁board的所有实体并移除Board资源，创建失败时没有board需要销毁
+    pub fn cleanup_board(board: Option<Res<Board>>, mut commands: Commands) {
+        if let Some(board) = board {
+            commands.entity(board.entity).despawn_recursive();
+        }
+        commands.remove_resource::<Board>();
+        commands.remove_resource::<ReplayRecorder>();
+    }
+}
 
-        // 将Board作为Resource添加到系统中
-        commands.insert_resource(Board {
-            tile_size,
-            tile_map,
-            bounds: Bounds2 {
-                size: Vec2::new(board_width, board_height),
-                // position: Vec2::new(board_position.x, board_position.y),
-                position: board_position.xy(),
-            },
+// 依赖board的系统的运行条件：处于running_state并且board已经创建
+fn board_exists(In(should_run): In<ShouldRun>, board: Option<Res<Board>>) -> ShouldRun {
+    match should_run {
+        ShouldRun::Yes | ShouldRun::YesAndCheckAgain if board.is_none() => ShouldRun::No,
+        should_run => should_run,
+    }
+}
+
+// 检查设置并取得用于计算board大小的主窗口
+fn board_window<'a>(
+    board_options: &BoardOptions,
+    windows: &'a Windows,
+) -> Result<&'a Window, BoardError> {
+    board_options
+        .validate()
+        .map_err(BoardError::InvalidOptions)?;
+    windows.get_primary().ok_or(BoardError::NoPrimaryWindow)
+}
+
+// 根据BoardOptions生成新的一局，安全开局时推迟到第一次翻开时再布置炸弹
+fn game_from_options(board_options: &BoardOptions) -> Game {
+    let (width, height) = board_options.map_size;
+    let seed = board_options.seed.unwrap_or_else(rand::random);
+    log::info!("Board seed: {}", seed);
+    let safe_start = board_options
+        .safe_start
+        .then_some(board_options.safe_start_policy);
+    Game::new(
+        width,
+        height,
+        board_options.bomb_count,
+        seed,
+        safe_start,
+        board_options.generation,
+    )
+}
+
+// 读取自动保存的游戏，没有存档或者存档无法读取时返回None
+fn load_autosave() -> Option<SavedGame> {
+    let path = SavedGame::default_path()?;
+    if !path.exists() {
+        return None;
+    }
+    match SavedGame::load(&path) {
+        Ok(saved) => {
+            log::info!("Resuming saved game from {}", path.display());
+            Some(saved)
+        }
+        Err(e) => {
+            log::error!("Failed to load saved game {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// 根据BoardOptions创建board的实体并添加Board资源，elapsed为已经用去的时间
+// replay为这局到目前为止的录像，为None时不录像（继续旧版本的存档时没有之前的输入）
+fn spawn_board(
+    commands: &mut Commands,
+    board_options: &BoardOptions,
+    window: &Window,
+    assets_server: &AssetServer,
+    game: Game,
+    elapsed: Duration,
+    replay: Option<Replay>,
+) {
+    let map_size = (game.width(), game.height());
+    let font = assets_server.load("fonts/pixeled.ttf");
+    let bomb_png = assets_server.load("sprites/bomb.png");
+    let flag_png = assets_server.load("sprites/flag.png");
+
+    #[cfg(feature = "debug")]
+    log::info!("{}", game.tile_map().console_output());
+
+    let (tile_size, board_position) = board_layout(board_options, window, map_size);
+
+    #[cfg(feature = "debug")]
+    log::info!("tile size is {}", tile_size);
+
+    let (board_width, board_height) =
+        (tile_size * map_size.0 as f32, tile_size * map_size.1 as f32);
+    let mut tiles = HashMap::with_capacity(map_size.0 as usize * map_size.1 as usize);
+    let mut covered_tiles = HashMap::with_capacity(map_size.0 as usize * map_size.1 as usize);
+
+    // 创建board
+    let board_entity = commands
+        .spawn_empty()
+        .insert(Name::new("Board"))
+        .insert(Transform::from_translation(board_position))
+        .insert(GlobalTransform::default())
+        .insert(ComputedVisibility::default())
+        .insert(Visibility { is_visible: true }) // 两个Visibility用来将父级的空元素显示，否则所有子元素都不能显示
+        .with_children(|parent| {
+            parent
+                .spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::WHITE,
+                        custom_size: Some(Vec2::new(board_width, board_height)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(board_width / 2., board_height / 2., 0.),
+                    ..default()
+                })
+                .insert(Name::new("Background"));
         })
+        .with_children(|parent| {
+            // 创建tile
+            sapwn_tiles(
+                parent,
+                &game,
+                tile_size,
+                board_options.tile_padding,
+                font,
+                bomb_png,
+                flag_png,
+                &mut tiles,
+                &mut covered_tiles,
+            );
+        })
+        .id();
+
+    match replay {
+        Some(replay) => commands.insert_resource(ReplayRecorder(replay)),
+        None => commands.remove_resource::<ReplayRecorder>(),
     }
+    // 继续已经翻开过tile并且还没有结束的游戏时继续计时，否则等到第一次翻开时开始
+    let tile_count = map_size.0 as usize * map_size.1 as usize;
+    let in_progress = !game.is_over() && game.covered_count() < tile_count;
+    // 将Board作为Resource添加到系统中
+    commands.insert_resource(Board {
+        game,
+        tile_size,
+        layout_tile_size: tile_size,
+        bounds: Bounds2 {
+            size: Vec2::new(board_width, board_height),
+            // position: Vec2::new(board_position.x, board_position.y),
+            position: board_position.xy(),
+        },
+        tiles,
+        covered_tiles,
+        clock: {
+            let mut clock = Stopwatch::new();
+            clock.set_elapsed(elapsed);
+            if !in_progress {
+                clock.pause();
+            }
+            clock
+        },
+        entity: board_entity,
+    })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn sapwn_tiles(
     parent: &mut ChildBuilder,
-    tile_map: &TileMap,
+    game: &Game,
     tile_size: f32,
     tile_padding: f32,
     font: Handle<Font>,
     image: Handle<Image>,
+    flag_image: Handle<Image>,
+    tiles: &mut HashMap<Coordinates, Entity>,
+    covered_tiles: &mut HashMap<Coordinates, Entity>,
 ) {
-    for (y, line) in tile_map.iter().enumerate() {
+    for (y, line) in game.tile_map().iter().enumerate() {
         for (x, tile) in line.iter().enumerate() {
-            parent
-                .spawn(SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::GRAY,
-                        custom_size: Some(Vec2::splat(tile_size - tile_padding)),
-                        ..default()
-                    },
-                    transform: Transform::from_xyz(
-                        tile_size * x as f32 + tile_size / 2.,
-                        tile_size * y as f32 + tile_size / 2.,
-                        1.,
-                    ),
+            let coordinates = Coordinates {
+                x: x as u16,
+                y: y as u16,
+            };
+            let mut cmd = parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::GRAY,
+                    custom_size: Some(Vec2::splat(tile_size - tile_padding)),
                     ..default()
-                })
-                .insert(Name::new(format!("Tile ({}, {})", x, y)))
-                .insert(Coordinates {
-                    x: x as u16,
-                    y: y as u16,
-                })
-                .with_children(|parent| {
-                    // 根据tile的不同类型进行不同的处理
-                    spawn_tile(
-                        parent,
-                        tile,
-                        tile_size,
-                        tile_padding,
-                        font.clone(),
-                        image.clone(),
-                    );
-                });
+                },
+                transform: Transform::from_xyz(
+                    tile_size * x as f32 + tile_size / 2.,
+                    tile_size * y as f32 + tile_size / 2.,
+                    1.,
+                ),
+                ..default()
+            });
+            cmd.insert(Name::new(format!("Tile ({}, {})", x, y)))
+                .insert(coordinates);
+            spawn_tile_content(
+                &mut cmd,
+                tile,
+                tile_size,
+                tile_padding,
+                font.clone(),
+                image.clone(),
+            );
+            // 已翻开的tile不需要覆盖层
+            let flag = match game.state_at(coordinates) {
+                Some(TileState::Uncovered) | None => {
+                    tiles.insert(coordinates, cmd.id());
+                    continue;
+                }
+                Some(TileState::Flagged) => Some(flag_image.clone()),
+                Some(TileState::Covered) => None,
+            };
+            cmd.with_children(|parent| {
+                let entity = spawn_cover(parent, tile_size, tile_padding, flag);
+                covered_tiles.insert(coordinates, entity);
+            });
+            tiles.insert(coordinates, cmd.id());
+        }
+    }
+}
+
+// 创建覆盖层，翻开时将其移除；flag不为None时在覆盖层上插旗
+pub(crate) fn spawn_cover(
+    parent: &mut ChildBuilder,
+    tile_size: f32,
+    tile_padding: f32,
+    flag: Option<Handle<Image>>,
+) -> Entity {
+    let mut cmd = parent.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: Color::DARK_GRAY,
+            custom_size: Some(Vec2::splat(tile_size - tile_padding)),
+            ..default()
+        },
+        transform: Transform::from_xyz(0., 0., 2.),
+        ..default()
+    });
+    cmd.insert(Name::new("Tile Cover"));
+    if let Some(flag) = flag {
+        cmd.with_children(|parent| spawn_flag(parent, flag, tile_size, tile_padding));
+    }
+    cmd.id()
+}
+
+// 在覆盖层上插旗
+pub(crate) fn spawn_flag(
+    parent: &mut ChildBuilder,
+    texture: Handle<Image>,
+    tile_size: f32,
+    tile_padding: f32,
+) {
+    parent
+        .spawn(SpriteBundle {
+            texture,
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(tile_size - tile_padding)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0., 0., 1.),
+            ..default()
+        })
+        .insert(Name::new("Flag"));
+}
+
+// 为tile添加对应的组件以及显示内容
+// Bomb和BombNeighbor组件挂在tile上，方便翻开时通过覆盖层的父级查询
+pub(crate) fn spawn_tile_content(
+    cmd: &mut EntityCommands,
+    tile: &Tile,
+    tile_size: f32,
+    tile_padding: f32,
+    font: Handle<Font>,
+    image: Handle<Image>,
+) {
+    match *tile {
+        Tile::Bomb => {
+            cmd.insert(Bomb);
+        }
+        Tile::BombNeighbor(count) => {
+            cmd.insert(BombNeighbor { count });
         }
+        Tile::Empty => return,
     }
+    cmd.with_children(|parent| {
+        // 根据tile的不同类型进行不同的处理
+        spawn_tile(parent, tile, tile_size, tile_padding, font, image);
+    });
 }
 
 // 根据tile的不同类型做不同的处理
@@ -178,7 +574,7 @@ fn spawn_tile(
                     texture: image,
                     ..default()
                 })
-                .insert(Bomb);
+                .insert(Name::new("Bomb"));
         }
         Tile::BombNeighbor(bomb_count) => {
             parent
@@ -187,7 +583,7 @@ fn spawn_tile(
                     font,
                     tile_size - tile_padding,
                 ))
-                .insert(BombNeighbor { count: bomb_count });
+                .insert(Name::new("Bomb Neighbor"));
         }
         Tile::Empty => (),
     }
@@ -217,7 +613,27 @@ fn bomb_count_text_bundle(bomb_count: u8, font: Handle<Font>, font_size: f32) ->
     }
 }
 
-// 根据当前窗口的大小以及设定的tile_size计算具体每个tile的实际大小
+// 根据窗口大小计算tile的大小以及board左下角的位置，显示HUD时位置包含HUD的偏移
+pub(crate) fn board_layout(
+    board_options: &BoardOptions,
+    window: &Window,
+    map_size: (u16, u16),
+) -> (f32, Vec3) {
+    // 显示HUD时board只占用HUD下方的区域
+    let hud_height = if board_options.hud { HUD_HEIGHT } else { 0. };
+    let tile_size = actual_tile_size(
+        (window.width(), window.height() - hud_height),
+        &board_options.tile_size,
+        map_size,
+    );
+    let board_size = (tile_size * map_size.0 as f32, tile_size * map_size.1 as f32);
+    let mut position = board_position(board_size, board_options.position);
+    if let BoardPosition::Centered { .. } = board_options.position {
+        position.y -= hud_height / 2.;
+    }
+    (tile_size, position)
+}
+
 fn actual_tile_size(
     (window_width, window_height): (f32, f32),
     tile_size: &TileSize,
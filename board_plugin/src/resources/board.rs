@@ -1,31 +1,70 @@
-use super::tile_map::TileMap;
-use crate::{bounds::Bounds2, components::Coordinates};
-use bevy::prelude::{Resource, Vec2, Window};
-
-#[derive(Debug, Resource)]
-pub struct Board {
-    pub bounds: Bounds2,
-    pub tile_size: f32,
-    pub tile_map: TileMap,
-}
-
-impl Board {
-    // 将鼠标的坐标转化成以board左下角为原点的坐标
-    // 若鼠标点击的位置不在board中则返回None
-    pub fn mouse_position(&self, window: &Window, mouse_pos: Vec2) -> Option<Coordinates> {
-        let win_width = window.width();
-        let win_height = window.height();
-        // 计算mouse相对于窗口左下角的位置
-        let window_mouse_pos = mouse_pos - Vec2::new(win_width / 2., win_height / 2.);
-        if self.bounds.in_bounds(window_mouse_pos) {
-            // 计算mouse相对于board左下角的位置
-            let board_mouse_pos = window_mouse_pos - self.bounds.position;
-            Some(Coordinates {
-                x: (board_mouse_pos.x / self.tile_size) as u16,
-                y: (board_mouse_pos.y / self.tile_size) as u16,
-            })
-        } else {
-            None
-        }
-    }
-}
+use crate::{bounds::Bounds2, components::Coordinates};
+use bevy::{
+    prelude::{Entity, GlobalTransform, Resource, Vec2},
+    time::Stopwatch,
+    utils::HashMap,
+};
+use minesweeper_core::{Game, Probabilities};
+
+#[derive(Debug, Resource)]
+pub struct Board {
+    pub game: Game, // 游戏规则以及状态
+    pub bounds: Bounds2,
+    pub tile_size: f32,                              // 显示的tile大小
+    pub layout_tile_size: f32, // 创建tile时使用的大小，board实体按tile_size / layout_tile_size缩放
+    pub tiles: HashMap<Coordinates, Entity>, // 每个坐标对应的tile
+    pub covered_tiles: HashMap<Coordinates, Entity>, // 尚未翻开的tile对应的覆盖层
+    pub clock: Stopwatch,      // 游戏用时，第一次翻开时开始计时，游戏结束时停止
+    pub entity: Entity,        // board的根节点
+}
+
+impl Board {
+    // 将世界坐标转化成tile的坐标，board_transform为board实体的GlobalTransform
+    // 若该位置不在board中则返回None
+    pub fn world_to_coordinates(
+        &self,
+        board_transform: &GlobalTransform,
+        world_pos: Vec2,
+    ) -> Option<Coordinates> {
+        // 转换到以board左下角为原点的坐标系，tile在其中按创建时的大小排列
+        let local = board_transform
+            .affine()
+            .inverse()
+            .transform_point3(world_pos.extend(0.));
+        let (x, y) = (
+            local.x / self.layout_tile_size,
+            local.y / self.layout_tile_size,
+        );
+        let in_board =
+            x >= 0. && y >= 0. && x < self.game.width() as f32 && y < self.game.height() as f32;
+        in_board.then_some(Coordinates {
+            x: x as u16,
+            y: y as u16,
+        })
+    }
+
+    // 获取某个坐标上的tile
+    pub fn tile_entity(&self, coords: &Coordinates) -> Option<&Entity> {
+        self.tiles.get(coords)
+    }
+
+    // 获取某个坐标上仍被覆盖的tile的覆盖层
+    pub fn cover_entity(&self, coords: &Coordinates) -> Option<&Entity> {
+        self.covered_tiles.get(coords)
+    }
+
+    // 当前插旗的数量
+    pub fn flagged_count(&self) -> usize {
+        self.game.flagged_count()
+    }
+
+    // 剩余雷数，即炸弹总数减去插旗数，插旗过多时为负数
+    pub fn remaining_mines(&self) -> i32 {
+        self.game.remaining_mines()
+    }
+
+    // 每个未翻开的tile是炸弹的概率
+    pub fn mine_probabilities(&self) -> Probabilities {
+        self.game.mine_probabilities()
+    }
+}
@@ -1,4 +1,7 @@
-use bevy::prelude::{Resource, Vec3};
+use std::fmt;
+
+use bevy::prelude::{MouseButton, Resource, Vec3};
+pub use minesweeper_core::{Generation, NoGuessFallback, SafeStartPolicy};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,7 +16,27 @@ pub enum BoardPosition {
     Custom(Vec3),
 }
 
+// 拖动视角使用的鼠标按键，该按键不再用于点击tile
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PanButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl From<PanButton> for MouseButton {
+    fn from(button: PanButton) -> Self {
+        match button {
+            PanButton::Left => MouseButton::Left,
+            PanButton::Right => MouseButton::Right,
+            PanButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+// 缺少的字段使用默认值，旧版本的存档和录像仍然可以读取
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
 pub struct BoardOptions {
     pub map_size: (u16, u16),
     pub bomb_count: u16,
@@ -21,6 +44,13 @@ pub struct BoardOptions {
     pub tile_size: TileSize,
     pub tile_padding: f32,
     pub safe_start: bool,
+    pub safe_start_policy: SafeStartPolicy,
+    pub seed: Option<u64>,      // 布置炸弹的随机种子，为None时随机生成
+    pub generation: Generation, // 炸弹布局的生成方式
+    pub hud: bool,              // 是否显示剩余雷数、用时以及重新开始按钮
+    pub autosave: bool,         // 退出时自动保存进行中的游戏，启动时继续上次的游戏
+    pub pan_zoom: bool,         // 允许拖动、键盘移动视角以及滚轮缩放，用于比窗口大的board
+    pub pan_button: PanButton,  // 拖动视角使用的鼠标按键
 }
 
 impl Default for TileSize {
@@ -46,6 +76,221 @@ impl Default for BoardOptions {
             tile_size: TileSize::default(),
             tile_padding: 0.,
             safe_start: false,
+            safe_start_policy: SafeStartPolicy::default(),
+            seed: None,
+            generation: Generation::default(),
+            hud: false,
+            autosave: false,
+            pan_zoom: false,
+            pan_button: PanButton::Middle,
+        }
+    }
+}
+
+// BoardOptions中无法创建board的设置
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardOptionsError {
+    // 宽或高为0
+    EmptyBoard {
+        width: u16,
+        height: u16,
+    },
+    // 边长超出坐标的计算范围或者tile总数过多
+    TooLarge {
+        width: u16,
+        height: u16,
+    },
+    // 至少需要一个不是炸弹的tile
+    TooManyMines {
+        bomb_count: u16,
+        tiles: u32,
+    },
+    // 放不下安全开局的区域
+    SafeStartImpossible {
+        bomb_count: u16,
+        safe_tiles: u32,
+        tiles: u32,
+    },
+    // 大小需要为正数且min不大于max
+    InvalidTileSize {
+        min: f32,
+        max: f32,
+    },
+    // 间距为负数或者不小于tile的大小
+    InvalidPadding {
+        padding: f32,
+        tile_size: f32,
+    },
+    // 按百分比计算的炸弹数超出u16的范围
+    DensityOverflow {
+        percent: f32,
+        tiles: u32,
+    },
+}
+
+impl fmt::Display for BoardOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyBoard { width, height } => {
+                write!(f, "board size {}x{} must not be empty", width, height)
+            }
+            Self::TooLarge { width, height } => write!(
+                f,
+                "board size {}x{} is too large (at most {} tiles per side and {} in total)",
+                width,
+                height,
+                BoardOptions::MAX_SIDE,
+                BoardOptions::MAX_TILES
+            ),
+            Self::TooManyMines { bomb_count, tiles } => write!(
+                f,
+                "{} mines leave no safe tile on a board of {} tiles",
+                bomb_count, tiles
+            ),
+            Self::SafeStartImpossible {
+                bomb_count,
+                safe_tiles,
+                tiles,
+            } => write!(
+                f,
+                "{} mines and a safe start area of {} tiles do not fit on a board of {} tiles",
+                bomb_count, safe_tiles, tiles
+            ),
+            Self::InvalidTileSize { min, max } => {
+                write!(f, "invalid tile size (min {}, max {})", min, max)
+            }
+            Self::InvalidPadding { padding, tile_size } => write!(
+                f,
+                "tile padding {} must be between 0 and the tile size {}",
+                padding, tile_size
+            ),
+            Self::DensityOverflow { percent, tiles } => write!(
+                f,
+                "{}% of {} tiles is more than the maximum of {} mines",
+                percent,
+                tiles,
+                u16::MAX
+            ),
         }
     }
 }
+
+impl std::error::Error for BoardOptionsError {}
+
+impl BoardOptions {
+    // 相邻坐标按i16计算，边长不能超过i16::MAX
+    pub const MAX_SIDE: u16 = i16::MAX as u16;
+    // 每个tile都对应几个实体，tile过多时无法创建board
+    pub const MAX_TILES: u32 = 1_000_000;
+
+    // 检查设置能否创建board
+    pub fn validate(&self) -> Result<(), BoardOptionsError> {
+        let (width, height) = self.map_size;
+        if width == 0 || height == 0 {
+            return Err(BoardOptionsError::EmptyBoard { width, height });
+        }
+        let tiles = width as u32 * height as u32;
+        if width > Self::MAX_SIDE || height > Self::MAX_SIDE || tiles > Self::MAX_TILES {
+            return Err(BoardOptionsError::TooLarge { width, height });
+        }
+        if self.bomb_count as u32 >= tiles {
+            return Err(BoardOptionsError::TooManyMines {
+                bomb_count: self.bomb_count,
+                tiles,
+            });
+        }
+        // 开局翻开一片区域（无猜布局也需要）时，第一次翻开的tile及其周围都不能有炸弹
+        let opening = (self.safe_start && self.safe_start_policy == SafeStartPolicy::Opening)
+            || matches!(self.generation, Generation::NoGuess { .. });
+        let safe_tiles = if opening {
+            (width.min(3) as u32) * (height.min(3) as u32)
+        } else {
+            1
+        };
+        if self.bomb_count as u32 + safe_tiles > tiles {
+            return Err(BoardOptionsError::SafeStartImpossible {
+                bomb_count: self.bomb_count,
+                safe_tiles,
+                tiles,
+            });
+        }
+
+        let (min, max) = match self.tile_size {
+            TileSize::Fixed(size) => (size, size),
+            TileSize::Adaptive { min, max } => (min, max),
+        };
+        if !(min > 0. && min <= max && max.is_finite()) {
+            return Err(BoardOptionsError::InvalidTileSize { min, max });
+        }
+        if !(self.tile_padding >= 0. && self.tile_padding < min) {
+            return Err(BoardOptionsError::InvalidPadding {
+                padding: self.tile_padding,
+                tile_size: min,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_options_are_valid() {
+        assert_eq!(BoardOptions::default().validate(), Ok(()));
+        let widest = BoardOptions {
+            map_size: (BoardOptions::MAX_SIDE, 1),
+            ..Default::default()
+        };
+        assert_eq!(widest.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let options = |f: fn(&mut BoardOptions)| {
+            let mut options = BoardOptions::default();
+            f(&mut options);
+            options.validate().unwrap_err()
+        };
+        assert!(matches!(
+            options(|o| o.map_size = (0, 10)),
+            BoardOptionsError::EmptyBoard { .. }
+        ));
+        assert!(matches!(
+            options(|o| o.map_size = (40000, 1)),
+            BoardOptionsError::TooLarge { .. }
+        ));
+        assert!(matches!(
+            options(|o| o.map_size = (u16::MAX, u16::MAX)),
+            BoardOptionsError::TooLarge { .. }
+        ));
+        assert!(matches!(
+            options(|o| o.bomb_count = 225),
+            BoardOptionsError::TooManyMines { .. }
+        ));
+        assert!(matches!(
+            options(|o| {
+                o.bomb_count = 220;
+                o.safe_start = true;
+                o.safe_start_policy = SafeStartPolicy::Opening;
+            }),
+            BoardOptionsError::SafeStartImpossible { safe_tiles: 9, .. }
+        ));
+        assert!(matches!(
+            options(|o| o.tile_size = TileSize::Adaptive { min: 20., max: 10. }),
+            BoardOptionsError::InvalidTileSize { .. }
+        ));
+        assert!(matches!(
+            options(|o| o.tile_padding = -1.),
+            BoardOptionsError::InvalidPadding { .. }
+        ));
+        assert!(matches!(
+            options(|o| {
+                o.tile_size = TileSize::Fixed(10.);
+                o.tile_padding = 10.;
+            }),
+            BoardOptionsError::InvalidPadding { .. }
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{BoardOptions, BoardOptionsError};
+
+// 难度预设，统计和记录按预设区分
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Beginner,     // 9x9，10个炸弹
+    Intermediate, // 16x16，40个炸弹
+    Expert,       // 30x16，99个炸弹
+    Custom,       // 使用BoardOptions中自定义的大小和炸弹数
+    Percent(f32), // 保持地图大小，炸弹数为tile总数的百分比
+}
+
+impl Difficulty {
+    // 经典难度
+    pub const CLASSIC: [Difficulty; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    // 经典难度的地图大小以及炸弹数
+    pub fn classic(&self) -> Option<((u16, u16), u16)> {
+        match self {
+            Self::Beginner => Some(((9, 9), 10)),
+            Self::Intermediate => Some(((16, 16), 40)),
+            Self::Expert => Some(((30, 16), 99)),
+            Self::Custom | Self::Percent(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Beginner => write!(f, "Beginner"),
+            Self::Intermediate => write!(f, "Intermediate"),
+            Self::Expert => write!(f, "Expert"),
+            Self::Custom => write!(f, "Custom"),
+            Self::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+impl BoardOptions {
+    // 默认设置加上难度对应的地图大小和炸弹数
+    pub fn from_difficulty(difficulty: Difficulty) -> Self {
+        let mut options = Self::default();
+        // 默认的地图大小下按百分比计算的炸弹数不会超出范围
+        let _ = options.set_difficulty(difficulty);
+        options
+    }
+
+    // 只修改地图大小和炸弹数，其余设置不变
+    // 按百分比计算的炸弹数限制在tile总数以内，超出u16的范围时返回错误并保持设置不变
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) -> Result<(), BoardOptionsError> {
+        if let Some((map_size, bomb_count)) = difficulty.classic() {
+            self.map_size = map_size;
+            self.bomb_count = bomb_count;
+        } else if let Difficulty::Percent(percent) = difficulty {
+            let tiles = self.map_size.0 as u32 * self.map_size.1 as u32;
+            let count = (tiles as f32 * percent / 100.)
+                .round()
+                .clamp(0., tiles as f32);
+            if count > u16::MAX as f32 {
+                return Err(BoardOptionsError::DensityOverflow { percent, tiles });
+            }
+            self.bomb_count = count as u16;
+        }
+        Ok(())
+    }
+
+    // 当前设置对应的难度，地图大小和炸弹数与经典难度都不同时为Custom
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::CLASSIC
+            .into_iter()
+            .find(|d| d.classic() == Some((self.map_size, self.bomb_count)))
+            .unwrap_or(Difficulty::Custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_round_trip() {
+        for difficulty in Difficulty::CLASSIC {
+            let options = BoardOptions::from_difficulty(difficulty);
+            assert_eq!(options.difficulty(), difficulty);
+            assert_eq!(options.validate(), Ok(()));
+        }
+        assert_eq!(BoardOptions::default().difficulty(), Difficulty::Custom);
+    }
+
+    #[test]
+    fn percent_keeps_map_size() {
+        let mut options = BoardOptions {
+            map_size: (10, 20),
+            ..Default::default()
+        };
+        assert_eq!(options.set_difficulty(Difficulty::Percent(15.)), Ok(()));
+        assert_eq!(options.map_size, (10, 20));
+        assert_eq!(options.bomb_count, 30);
+        assert_eq!(options.set_difficulty(Difficulty::Custom), Ok(()));
+        assert_eq!(options.bomb_count, 30);
+        assert_eq!(options.set_difficulty(Difficulty::Percent(150.)), Ok(()));
+        assert_eq!(options.bomb_count, 200);
+    }
+
+    #[test]
+    fn percent_overflow() {
+        let mut options = BoardOptions {
+            map_size: (300, 300),
+            bomb_count: 40,
+            ..Default::default()
+        };
+        assert!(matches!(
+            options.set_difficulty(Difficulty::Percent(80.)),
+            Err(BoardOptionsError::DensityOverflow { tiles: 90000, .. })
+        ));
+        assert_eq!(options.bomb_count, 40);
+    }
+}
@@ -1,6 +1,10 @@
 pub mod board;
 mod board_options;
-pub(crate) mod tile;
-pub(crate) mod tile_map;
+mod difficulty;
+mod probability_overlay;
+pub mod replay;
+pub mod save;
 
 pub use board_options::*;
+pub use difficulty::Difficulty;
+pub use probability_overlay::ProbabilityOverlay;
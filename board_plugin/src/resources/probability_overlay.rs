@@ -0,0 +1,7 @@
+use bevy::prelude::Resource;
+
+// 炸弹概率的调试覆盖层，开启时按照概率为每个覆盖层着色
+#[derive(Debug, Default, Resource)]
+pub struct ProbabilityOverlay {
+    pub enabled: bool,
+}
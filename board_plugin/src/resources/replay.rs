@@ -0,0 +1,278 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::Resource;
+use minesweeper_core::{Game, TileMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{components::Coordinates, resources::BoardOptions};
+
+// 保存录像的目录
+pub const REPLAY_DIR: &str = "replays";
+
+// 点击使用的按键
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayButton {
+    Left,  // 翻开
+    Right, // 插旗
+    Chord, // 中键或左右键同时按下
+}
+
+// 录像中的一次输入
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayInput {
+    Click {
+        coords: Coordinates,
+        button: ReplayButton,
+    },
+    Undo,
+    Redo,
+    Hint, // 请求提示，回放时同样计入提示次数
+}
+
+impl ReplayInput {
+    // 同一帧内不同类型的输入由不同的system依次处理，与发送的先后无关
+    pub fn order(&self) -> u8 {
+        match self {
+            Self::Click {
+                button: ReplayButton::Left,
+                ..
+            } => 0,
+            Self::Click {
+                button: ReplayButton::Chord,
+                ..
+            } => 1,
+            Self::Click {
+                button: ReplayButton::Right,
+                ..
+            } => 2,
+            Self::Undo => 3,
+            Self::Redo => 4,
+            Self::Hint => 5,
+        }
+    }
+}
+
+// 带有时间的输入，offset为相对于开始计时的时间
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    pub offset: Duration,
+    pub input: ReplayInput,
+}
+
+// 一局游戏的录像：布局、选项以及玩家的所有输入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub tile_map: Option<TileMap>, // 炸弹布局，还未布置炸弹时为None，此时使用seed重新生成
+    pub options: BoardOptions,
+    pub events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    pub fn new(options: BoardOptions, seed: u64) -> Self {
+        Self {
+            seed,
+            tile_map: None,
+            options,
+            events: Vec::new(),
+        }
+    }
+
+    // 按照录像的布局创建一局新游戏
+    pub fn game(&self) -> Game {
+        match self.tile_map.as_ref() {
+            Some(tile_map) => Game::from_tile_map(tile_map.clone()),
+            None => {
+                let (width, height) = self.options.map_size;
+                let safe_start = self
+                    .options
+                    .safe_start
+                    .then_some(self.options.safe_start_policy);
+                Game::new(
+                    width,
+                    height,
+                    self.options.bomb_count,
+                    self.seed,
+                    safe_start,
+                    self.options.generation,
+                )
+            }
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        ron::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)
+    }
+
+    // 保存到录像目录下，文件名包含种子以及保存的时间，返回保存的路径
+    pub fn save_to_dir(&self, dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = dir
+            .as_ref()
+            .join(format!("replay-{}-{}.ron", self.seed, timestamp));
+        self.save(&path)?;
+        Ok(path)
+    }
+}
+
+// 当前这局游戏的录像
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayRecorder(pub Replay);
+
+// 正在回放的录像，宿主程序插入该资源后创建的board会使用录像的布局并自动回放输入
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub next: usize,       // 下一个要回放的输入
+    pub elapsed: Duration, // 已经回放的时间
+    pub speed: f32,        // 回放速度的倍数
+    pub paused: bool,
+}
+
+impl ReplayPlayback {
+    pub const MIN_SPEED: f32 = 0.25;
+    pub const MAX_SPEED: f32 = 16.;
+
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            elapsed: Duration::ZERO,
+            speed: 1.,
+            paused: false,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+
+    // 推进回放时间，返回这段时间内需要回放的输入
+    // 在同一帧内处理会改变顺序的输入留到之后的帧
+    pub fn advance(&mut self, delta: Duration) -> Vec<ReplayInput> {
+        if self.paused {
+            return Vec::new();
+        }
+        self.elapsed += delta.mul_f32(self.speed);
+        let mut inputs: Vec<ReplayInput> = Vec::new();
+        while let Some(event) = self.replay.events.get(self.next) {
+            if event.offset > self.elapsed {
+                break;
+            }
+            if inputs
+                .last()
+                .is_some_and(|last| last.order() > event.input.order())
+            {
+                break;
+            }
+            inputs.push(event.input);
+            self.next += 1;
+        }
+        inputs
+    }
+
+    // 单步回放下一个输入
+    pub fn step(&mut self) -> Option<ReplayInput> {
+        let event = self.replay.events.get(self.next)?;
+        self.elapsed = self.elapsed.max(event.offset);
+        self.next += 1;
+        Some(event.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        let mut replay = Replay::new(BoardOptions::default(), 7);
+        for (secs, x) in [(0, 0), (1, 1), (3, 2)] {
+            replay.events.push(ReplayEvent {
+                offset: Duration::from_secs(secs),
+                input: ReplayInput::Click {
+                    coords: Coordinates { x, y: 0 },
+                    button: ReplayButton::Left,
+                },
+            });
+        }
+        replay
+    }
+
+    #[test]
+    fn playback_follows_offsets_and_speed() {
+        let mut playback = ReplayPlayback::new(replay());
+        assert_eq!(playback.advance(Duration::from_millis(500)).len(), 1);
+        playback.speed = 2.;
+        assert_eq!(playback.advance(Duration::from_millis(500)).len(), 1);
+        playback.paused = true;
+        assert!(playback.advance(Duration::from_secs(10)).is_empty());
+        assert!(playback.step().is_some());
+        assert!(playback.is_finished());
+        assert_eq!(playback.elapsed, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn playback_keeps_input_order() {
+        let mut replay = Replay::new(BoardOptions::default(), 7);
+        let click = |x, button| ReplayInput::Click {
+            coords: Coordinates { x, y: 0 },
+            button,
+        };
+        for input in [
+            click(0, ReplayButton::Left),
+            click(1, ReplayButton::Right),
+            click(2, ReplayButton::Left),
+            ReplayInput::Undo,
+            ReplayInput::Hint,
+            click(3, ReplayButton::Left),
+        ] {
+            replay.events.push(ReplayEvent {
+                offset: Duration::ZERO,
+                input,
+            });
+        }
+        let mut playback = ReplayPlayback::new(replay);
+        assert_eq!(
+            playback.advance(Duration::ZERO),
+            [click(0, ReplayButton::Left), click(1, ReplayButton::Right)]
+        );
+        assert_eq!(
+            playback.advance(Duration::ZERO),
+            [
+                click(2, ReplayButton::Left),
+                ReplayInput::Undo,
+                ReplayInput::Hint
+            ]
+        );
+        assert_eq!(
+            playback.advance(Duration::ZERO),
+            [click(3, ReplayButton::Left)]
+        );
+        assert!(playback.is_finished());
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let replay = replay();
+        let content = ron::to_string(&replay).unwrap();
+        let loaded: Replay = ron::from_str(&content).unwrap();
+        assert_eq!(loaded.seed, replay.seed);
+        assert_eq!(loaded.events, replay.events);
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use minesweeper_core::Game;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{replay::Replay, BoardOptions};
+
+// 存档格式的版本，格式改变时增加版本号并在load中兼容旧版本
+// 版本2增加了录像
+pub const SAVE_VERSION: u32 = 2;
+
+// 一局进行中的游戏的存档：炸弹布局、每个tile的状态、种子、用时以及到目前为止的录像
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub options: BoardOptions,
+    pub game: Game,
+    pub elapsed: Duration,
+    #[serde(default)]
+    pub replay: Option<Replay>, // 版本1的存档中没有录像
+}
+
+// 只读取版本号，用来决定按照哪个版本的格式解析
+#[derive(Deserialize)]
+#[serde(rename = "SavedGame")]
+struct SaveHeader {
+    version: u32,
+}
+
+impl SavedGame {
+    pub fn new(
+        options: BoardOptions,
+        game: Game,
+        elapsed: Duration,
+        replay: Option<Replay>,
+    ) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            options,
+            game,
+            elapsed,
+            replay,
+        }
+    }
+
+    // 默认的存档位置：用户数据目录下的minesweeper/save.ron
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("minesweeper").join("save.ron"))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        let header: SaveHeader = ron::from_str(&content).map_err(invalid)?;
+        match header.version {
+            1 | 2 => ron::from_str(&content).map_err(invalid),
+            version => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported save version {}", version),
+            )),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::replay::{ReplayButton, ReplayEvent, ReplayInput};
+    use minesweeper_core::{Coordinates, Generation, SafeStartPolicy};
+
+    #[test]
+    fn save_and_load() {
+        let mut game = Game::new(
+            9,
+            9,
+            10,
+            1,
+            Some(SafeStartPolicy::Opening),
+            Generation::Random,
+        );
+        game.reveal(Coordinates { x: 4, y: 4 });
+        let mut replay = Replay::new(BoardOptions::default(), game.seed());
+        replay.events.push(ReplayEvent {
+            offset: Duration::ZERO,
+            input: ReplayInput::Click {
+                coords: Coordinates { x: 4, y: 4 },
+                button: ReplayButton::Left,
+            },
+        });
+        let saved = SavedGame::new(
+            BoardOptions::default(),
+            game,
+            Duration::from_secs(42),
+            Some(replay),
+        );
+
+        let path =
+            std::env::temp_dir().join(format!("minesweeper-save-{}.ron", std::process::id()));
+        saved.save(&path).unwrap();
+        let loaded = SavedGame::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.version, SAVE_VERSION);
+        assert_eq!(loaded.elapsed, saved.elapsed);
+        assert_eq!(loaded.game.seed(), saved.game.seed());
+        assert_eq!(loaded.game.covered_count(), saved.game.covered_count());
+        assert_eq!(
+            loaded.replay.map(|replay| replay.events),
+            saved.replay.map(|replay| replay.events)
+        );
+    }
+
+    #[test]
+    fn version_one_save_has_no_replay() {
+        let mut saved = SavedGame::new(
+            BoardOptions::default(),
+            Game::new(5, 5, 3, 0, None, Generation::Random),
+            Duration::ZERO,
+            None,
+        );
+        saved.version = 1;
+        // 版本1的存档没有replay字段
+        let content = ron::ser::to_string_pretty(&saved, ron::ser::PrettyConfig::default())
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("replay:"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(!content.contains("replay"));
+        let path = std::env::temp_dir().join(format!("minesweeper-v1-{}.ron", std::process::id()));
+        fs::write(&path, content).unwrap();
+        let loaded = SavedGame::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.version, 1);
+        assert!(loaded.replay.is_none());
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut saved = SavedGame::new(
+            BoardOptions::default(),
+            Game::new(5, 5, 3, 0, None, Generation::Random),
+            Duration::ZERO,
+            None,
+        );
+        saved.version = SAVE_VERSION + 1;
+        let path =
+            std::env::temp_dir().join(format!("minesweeper-future-{}.ron", std::process::id()));
+        saved.save(&path).unwrap();
+        let result = SavedGame::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::camera::RenderTarget,
+    window::WindowId,
+};
+
+use crate::{
+    resources::{board::Board, replay::ReplayPlayback, BoardOptions},
+    systems::{hud::HUD_HEIGHT, input::cursor_world_position},
+};
+
+// 滚轮每滚动一行的缩放倍数
+const ZOOM_STEP: f32 = 1.1;
+// 以像素为单位的滚动量换算为行数
+const PIXELS_PER_LINE: f32 = 50.;
+// 最多放大到原来的4倍
+const MIN_SCALE: f32 = 0.25;
+// 按键移动视角的速度，单位为每秒移动的屏幕像素
+const PAN_SPEED: f32 = 600.;
+
+type CameraQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Camera,
+        &'static mut Transform,
+        &'static mut OrthographicProjection,
+    ),
+>;
+
+// 滚轮以鼠标位置为中心缩放视角
+pub fn zoom_camera(
+    board_options: Res<BoardOptions>,
+    windows: Res<Windows>,
+    mut wheel_evr: EventReader<MouseWheel>,
+    mut cameras: CameraQuery,
+    globals: Query<(&Camera, &GlobalTransform)>,
+) {
+    let lines: f32 = wheel_evr
+        .iter()
+        .map(|e| match e.unit {
+            MouseScrollUnit::Line => e.y,
+            MouseScrollUnit::Pixel => e.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    if !board_options.pan_zoom || lines == 0. {
+        return;
+    }
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let Some((_, mut transform, mut projection)) = primary_camera(&mut cameras) else {
+        return;
+    };
+
+    let old_scale = projection.scale;
+    projection.scale = (old_scale * ZOOM_STEP.powf(-lines)).max(MIN_SCALE);
+    // 缩放后鼠标下方的世界坐标保持不变
+    if let Some(cursor) = cursor_world_position(window, &globals) {
+        let offset = cursor - transform.translation.truncate();
+        let shift = offset * (1. - projection.scale / old_scale);
+        transform.translation += shift.extend(0.);
+    }
+}
+
+// 按住拖动键拖动或者使用WASD/Shift+方向键移动视角，并将视角限制在board的范围内
+// 不按Shift时方向键用于移动键盘光标，回放录像时方向键用于控制回放
+#[allow(clippy::too_many_arguments)]
+pub fn pan_camera(
+    board: Res<Board>,
+    board_options: Res<BoardOptions>,
+    windows: Res<Windows>,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut motion_evr: EventReader<MouseMotion>,
+    mut cameras: CameraQuery,
+) {
+    let drag: Vec2 = motion_evr.iter().map(|e| e.delta).sum();
+    if !board_options.pan_zoom {
+        return;
+    }
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let Some((_, mut transform, mut projection)) = primary_camera(&mut cameras) else {
+        return;
+    };
+
+    // 屏幕上的移动量，y轴向上
+    let mut movement = Vec2::ZERO;
+    if buttons.pressed(board_options.pan_button.into()) {
+        movement += Vec2::new(-drag.x, drag.y);
+    }
+    // Ctrl+S用于保存录像
+    let letters = !keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let arrows = playback.is_none() && keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let mut direction = Vec2::ZERO;
+    for (key, arrow, step) in [
+        (KeyCode::A, KeyCode::Left, Vec2::NEG_X),
+        (KeyCode::D, KeyCode::Right, Vec2::X),
+        (KeyCode::S, KeyCode::Down, Vec2::NEG_Y),
+        (KeyCode::W, KeyCode::Up, Vec2::Y),
+    ] {
+        if (letters && keys.pressed(key)) || (arrows && keys.pressed(arrow)) {
+            direction += step;
+        }
+    }
+    movement += direction * PAN_SPEED * time.delta_seconds();
+
+    // 显示HUD时可见区域为HUD下方的部分
+    let hud_height = if board_options.hud { HUD_HEIGHT } else { 0. };
+    let view = Vec2::new(window.width(), window.height() - hud_height).max(Vec2::ONE);
+    // 缩小到整个board可见后不再继续缩小
+    let fit = (board.bounds.size / view).max_element().max(1.);
+    if projection.scale > fit {
+        projection.scale = fit;
+    }
+    let scale = projection.scale;
+
+    // 可见区域的中心在相机中心下方HUD高度一半的位置
+    let hud_offset = Vec2::new(0., hud_height / 2. * scale);
+    let center = transform.translation.truncate() + movement * scale - hud_offset;
+    let half = view / 2. * scale;
+    let min = board.bounds.position;
+    let max = board.bounds.position + board.bounds.size;
+    // board比可见区域小的方向上保持居中
+    let clamp = |center: f32, half: f32, min: f32, max: f32| {
+        if max - min > half * 2. {
+            center.clamp(min + half, max - half)
+        } else {
+            (min + max) / 2.
+        }
+    };
+    let center = Vec2::new(
+        clamp(center.x, half.x, min.x, max.x),
+        clamp(center.y, half.y, min.y, max.y),
+    );
+    let translation = (center + hud_offset).extend(transform.translation.z);
+    // 视角没有变化时不修改，避免触发变化检测
+    if transform.translation != translation {
+        transform.translation = translation;
+    }
+}
+
+// 渲染到主窗口的相机中priority最高的一个
+fn primary_camera<'a>(
+    cameras: &'a mut CameraQuery,
+) -> Option<(
+    &'a Camera,
+    Mut<'a, Transform>,
+    Mut<'a, OrthographicProjection>,
+)> {
+    cameras
+        .iter_mut()
+        .filter(|(camera, ..)| {
+            camera.is_active && camera.target == RenderTarget::Window(WindowId::primary())
+        })
+        .max_by_key(|(camera, ..)| camera.priority)
+}
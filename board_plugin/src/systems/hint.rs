@@ -0,0 +1,108 @@
+use bevy::{
+    log,
+    prelude::{
+        Color, Commands, Entity, EventReader, EventWriter, Input, KeyCode, Query, Res, ResMut,
+        Sprite,
+    },
+};
+use minesweeper_core::HintKind;
+
+use crate::{
+    components::hint::HintHighlight,
+    events::{HintEvent, NewGame, TileChordEvent, TileMarkEvent, TileTriggerEvent},
+    resources::{board::Board, replay::ReplayPlayback},
+};
+
+const SAFE_HINT_COLOR: Color = Color::rgb(0.3, 0.6, 0.9);
+const GUESS_HINT_COLOR: Color = Color::rgb(0.9, 0.8, 0.2);
+const REASON_COLOR: Color = Color::rgb(0.6, 0.8, 1.0);
+
+// ?键请求提示，hjkl用于移动键盘光标；回放录像时由录像发送提示请求
+pub fn hint_input(
+    keys: Res<Input<KeyCode>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut hint_ewr: EventWriter<HintEvent>,
+) {
+    if playback.is_none() && keys.just_pressed(KeyCode::Slash) {
+        hint_ewr.send(HintEvent);
+    }
+}
+
+// 高亮提示的tile以及证明它安全的数字块
+pub fn show_hint(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut sprites: Query<&mut Sprite>,
+    mut hint_evr: EventReader<HintEvent>,
+) {
+    if hint_evr.iter().last().is_none() {
+        return;
+    }
+    let Some(hint) = board.game.request_hint() else {
+        log::info!("No hint available");
+        return;
+    };
+    let mut reasons: Vec<String> = hint.reasons.iter().map(|c| c.to_string()).collect();
+    if hint.mine_count {
+        reasons.push("the remaining mine count".to_string());
+    }
+    let color = match hint.kind {
+        HintKind::Safe => {
+            log::info!(
+                "Hint: {} is safe, proven by {}",
+                hint.coords,
+                reasons.join(", ")
+            );
+            SAFE_HINT_COLOR
+        }
+        HintKind::Guess { probability } => {
+            log::info!(
+                "Hint: no safe tile, {} has the lowest mine probability ({:.0}%)",
+                hint.coords,
+                probability * 100.
+            );
+            GUESS_HINT_COLOR
+        }
+    };
+
+    let mut highlight = |entity: Option<&Entity>, color: Color| {
+        let Some(&entity) = entity else {
+            return;
+        };
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            commands.entity(entity).insert(HintHighlight {
+                original: sprite.color,
+            });
+            sprite.color = color;
+        }
+    };
+    highlight(board.cover_entity(&hint.coords), color);
+    for coords in hint.reasons.iter() {
+        highlight(board.tile_entity(coords), REASON_COLOR);
+    }
+}
+
+// 玩家进行操作或者请求新的提示时清除之前的高亮
+pub fn clear_hint(
+    mut commands: Commands,
+    mut highlighted: Query<(Entity, &HintHighlight, &mut Sprite)>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+    mut hint_evr: EventReader<HintEvent>,
+    mut new_game_evr: EventReader<NewGame>,
+) {
+    let acted = tile_trigger_evr.iter().count()
+        + tile_mark_evr.iter().count()
+        + tile_chord_evr.iter().count()
+        + hint_evr.iter().count()
+        + new_game_evr.iter().count()
+        > 0;
+    if !acted {
+        return;
+    }
+    for (entity, highlight, mut sprite) in highlighted.iter_mut() {
+        sprite.color = highlight.original;
+        commands.entity(entity).remove::<HintHighlight>();
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    log,
+    prelude::{
+        AssetServer, BuildChildren, Children, Color, Commands, DespawnRecursiveExt, EventReader,
+        EventWriter, Input, KeyCode, Query, Res, ResMut, Sprite,
+    },
+};
+use minesweeper_core::TileState;
+
+use crate::{
+    components::Uncover,
+    events::{GameLost, GameWon, Redo, Undo},
+    resources::{board::Board, BoardOptions},
+    spawn_cover, spawn_flag,
+    systems::uncover::handle_status,
+};
+
+// Ctrl+Z撤销，Ctrl+Y或Ctrl+Shift+Z重做
+pub fn history_input(
+    keys: Res<Input<KeyCode>>,
+    mut undo_ewr: EventWriter<Undo>,
+    mut redo_ewr: EventWriter<Redo>,
+) {
+    if !keys.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    if keys.just_pressed(KeyCode::Z) && !shift {
+        undo_ewr.send(Undo);
+    } else if keys.just_pressed(KeyCode::Y) || keys.just_pressed(KeyCode::Z) {
+        redo_ewr.send(Redo);
+    }
+}
+
+// 撤销或重做玩家的操作，并按照游戏状态重新同步覆盖层和旗帜
+#[allow(clippy::too_many_arguments)]
+pub fn history_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Res<BoardOptions>,
+    asset_server: Res<AssetServer>,
+    mut undo_evr: EventReader<Undo>,
+    mut redo_evr: EventReader<Redo>,
+    mut game_won_ewr: EventWriter<GameWon>,
+    mut game_lost_ewr: EventWriter<GameLost>,
+    children: Query<&Children>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let status = board.game.status();
+    let mut changed = false;
+    for _ in undo_evr.iter() {
+        if let Some(action) = board.game.undo() {
+            log::info!("Undo {:?}", action);
+            changed = true;
+        }
+    }
+    for _ in redo_evr.iter() {
+        if let Some(action) = board.game.redo() {
+            log::info!("Redo {:?}", action);
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+
+    let (tile_size, tile_padding) = (board.layout_tile_size, board_options.tile_padding);
+    let flag_png = asset_server.load("sprites/flag.png");
+    let tiles: Vec<_> = board.tiles.iter().map(|(c, e)| (*c, *e)).collect();
+    for (coords, tile) in tiles {
+        // 撤销失败时需要清除踩中炸弹的标记
+        if let Ok(mut sprite) = sprites.get_mut(tile) {
+            sprite.color = Color::GRAY;
+        }
+        let state = board.game.state_at(coords);
+        let cover = board.cover_entity(&coords).copied();
+        match (state, cover) {
+            (Some(TileState::Uncovered), Some(cover)) => {
+                board.covered_tiles.remove(&coords);
+                commands.entity(cover).insert(Uncover);
+            }
+            (Some(TileState::Covered | TileState::Flagged), None) => {
+                let flag = (state == Some(TileState::Flagged)).then(|| flag_png.clone());
+                commands.entity(tile).with_children(|parent| {
+                    let cover = spawn_cover(parent, tile_size, tile_padding, flag);
+                    board.covered_tiles.insert(coords, cover);
+                });
+            }
+            (Some(TileState::Covered | TileState::Flagged), Some(cover)) => {
+                if let Ok(mut sprite) = sprites.get_mut(cover) {
+                    sprite.color = Color::DARK_GRAY;
+                }
+                let has_flag = children.get(cover).is_ok_and(|c| !c.is_empty());
+                let flagged = state == Some(TileState::Flagged);
+                if has_flag && !flagged {
+                    commands.entity(cover).despawn_descendants();
+                } else if flagged && !has_flag {
+                    commands.entity(cover).with_children(|parent| {
+                        spawn_flag(parent, flag_png.clone(), tile_size, tile_padding)
+                    });
+                }
+            }
+            _ => (),
+        }
+    }
+    // 只在游戏状态改变时（撤销踩中炸弹或者重做结束游戏的操作）开始或停止计时
+    if board.game.status() != status {
+        handle_status(&mut board, &mut game_won_ewr, &mut game_lost_ewr);
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::{
+    prelude::*,
+    time::{Stopwatch, Time},
+};
+
+use minesweeper_core::GameStatus;
+
+use crate::{
+    components::hud::{GameClock, HudRoot, MineCounter, ResetButton},
+    events::NewGame,
+    resources::{board::Board, BoardOptions},
+};
+
+pub const HUD_HEIGHT: f32 = 80.;
+const HUD_FONT_SIZE: f32 = 28.;
+const FACE_PLAYING: &str = ":)";
+const FACE_WON: &str = "B)";
+const FACE_LOST: &str = "X(";
+
+// 在窗口顶部创建HUD：左侧为剩余雷数，中间为重新开始按钮，右侧为用时
+pub fn spawn_hud(
+    mut commands: Commands,
+    board_options: Res<BoardOptions>,
+    assets_server: Res<AssetServer>,
+) {
+    if !board_options.hud {
+        return;
+    }
+    let font = assets_server.load("fonts/pixeled.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: HUD_FONT_SIZE,
+        color: Color::RED,
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(0.),
+                    left: Val::Px(0.),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Px(HUD_HEIGHT)),
+                justify_content: JustifyContent::SpaceAround,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::DARK_GRAY.into(),
+            ..default()
+        })
+        .insert(Name::new("HUD"))
+        .insert(HudRoot)
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_section("000", text_style.clone()))
+                .insert(Name::new("Mine Counter"))
+                .insert(MineCounter);
+            parent
+                .spawn(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(HUD_HEIGHT - 20.), Val::Px(HUD_HEIGHT - 20.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::GRAY.into(),
+                    ..default()
+                })
+                .insert(Name::new("Reset Button"))
+                .insert(ResetButton)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        FACE_PLAYING,
+                        TextStyle {
+                            color: Color::YELLOW,
+                            ..text_style.clone()
+                        },
+                    ));
+                });
+            parent
+                .spawn(TextBundle::from_section("000", text_style))
+                .insert(Name::new("Game Clock"))
+                .insert(GameClock);
+        });
+}
+
+pub fn despawn_hud(mut commands: Commands, hud: Query<Entity, With<HudRoot>>) {
+    for entity in hud.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// 游戏用时计时
+pub fn tick_clock(time: Res<Time>, mut board: ResMut<Board>) {
+    board.clock.tick(time.delta());
+}
+
+pub fn update_mine_counter(board: Res<Board>, mut texts: Query<&mut Text, With<MineCounter>>) {
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("{:03}", board.remaining_mines().clamp(-99, 999));
+    }
+}
+
+pub fn update_clock(board: Res<Board>, mut texts: Query<&mut Text, With<GameClock>>) {
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = clock_text(&board.clock);
+    }
+}
+
+// 按钮上的表情：进行中为笑脸，胜利戴墨镜，失败为哭脸
+pub fn update_face(
+    board: Res<Board>,
+    buttons: Query<&Children, With<ResetButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    let face = match board.game.status() {
+        GameStatus::Playing => FACE_PLAYING,
+        GameStatus::Won => FACE_WON,
+        GameStatus::Lost { .. } => FACE_LOST,
+    };
+    for children in buttons.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = face.to_string();
+            }
+        }
+    }
+}
+
+type ResetButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static mut BackgroundColor),
+    (Changed<Interaction>, With<ResetButton>),
+>;
+
+// 点击按钮重新开始新的一局
+pub fn reset_button(mut buttons: ResetButtonQuery, mut new_game_ewr: EventWriter<NewGame>) {
+    for (interaction, mut color) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = Color::DARK_GRAY.into();
+                new_game_ewr.send(NewGame::default());
+            }
+            Interaction::Hovered => *color = Color::SILVER.into(),
+            Interaction::None => *color = Color::GRAY.into(),
+        }
+    }
+}
+
+fn clock_text(clock: &Stopwatch) -> String {
+    format!("{:03}", (clock.elapsed_secs() as u32).min(999))
+}
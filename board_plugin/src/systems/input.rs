@@ -1,36 +1,119 @@
-use bevy::{
-    input::{mouse::MouseButtonInput, ButtonState},
-    log,
-    prelude::{EventReader, MouseButton, Res, Windows},
-};
-
-use crate::resources::board::Board;
-
-pub fn event_handle(
-    board: Res<Board>,
-    mut event_reader: EventReader<MouseButtonInput>,
-    windows: Res<Windows>,
-) {
-    if let Some(event) = event_reader.iter().next() {
-        if let ButtonState::Released = event.state {
-            return;
-        }
-        let window = windows.get_primary().unwrap();
-        let mouse_pos = window.cursor_position().unwrap();
-        log::trace!("Mouse Buttion Pressed: {:?} as {}", event.button, mouse_pos);
-        let coords = board.mouse_position(window, mouse_pos);
-        if let None = coords {
-            return;
-        }
-        let coords = coords.unwrap();
-        match event.button {
-            MouseButton::Left => {
-                log::info!("Trying to uncover tile on {}", coords);
-            }
-            MouseButton::Right => {
-                log::info!("Trying to mark tile on {}", coords);
-            }
-            _ => (),
-        }
-    }
-}
+use bevy::{
+    input::{mouse::MouseButtonInput, ButtonState},
+    log,
+    prelude::{
+        Camera, EventReader, EventWriter, GlobalTransform, Input, KeyCode, MouseButton, Query, Res,
+        Vec2, Window, Windows,
+    },
+    render::camera::RenderTarget,
+    window::WindowId,
+};
+
+use crate::{
+    events::{NewGame, TileChordEvent, TileMarkEvent, TileTriggerEvent},
+    resources::{board::Board, replay::ReplayPlayback, BoardOptions},
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn event_handle(
+    board: Res<Board>,
+    board_options: Res<BoardOptions>,
+    mut event_reader: EventReader<MouseButtonInput>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
+    buttons: Res<Input<MouseButton>>,
+    windows: Res<Windows>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    transforms: Query<&GlobalTransform>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    // 回放录像时不响应点击
+    if playback.is_some() {
+        return;
+    }
+    if let Some(event) = event_reader.iter().next() {
+        if let ButtonState::Released = event.state {
+            return;
+        }
+        // 游戏结束后不再响应点击
+        if board.game.is_over() {
+            return;
+        }
+        // 拖动视角的按键不用于点击tile
+        if board_options.pan_zoom && event.button == board_options.pan_button.into() {
+            return;
+        }
+        let Some(window) = windows.get_primary() else {
+            return;
+        };
+        let Some(world_pos) = cursor_world_position(window, &cameras) else {
+            return;
+        };
+        log::trace!("Mouse Buttion Pressed: {:?} as {}", event.button, world_pos);
+        let Ok(board_transform) = transforms.get(board.entity) else {
+            return;
+        };
+        let Some(coords) = board.world_to_coordinates(board_transform, world_pos) else {
+            return;
+        };
+        // 中键或者左右键同时按下时进行双击翻开
+        let chord = match event.button {
+            MouseButton::Left => buttons.pressed(MouseButton::Right),
+            MouseButton::Right => buttons.pressed(MouseButton::Left),
+            MouseButton::Middle => true,
+            _ => false,
+        };
+        if chord {
+            log::info!("Trying to chord tile on {}", coords);
+            tile_chord_ewr.send(TileChordEvent(coords));
+            return;
+        }
+        match event.button {
+            MouseButton::Left => {
+                log::info!("Trying to uncover tile on {}", coords);
+                tile_trigger_ewr.send(TileTriggerEvent(coords));
+            }
+            MouseButton::Right => {
+                log::info!("Trying to mark tile on {}", coords);
+                tile_mark_ewr.send(TileMarkEvent(coords));
+            }
+            _ => (),
+        }
+    }
+}
+
+// R重新开始新的一局，Shift+R重玩当前布局
+pub fn new_game_input(keys: Res<Input<KeyCode>>, mut new_game_ewr: EventWriter<NewGame>) {
+    if keys.just_pressed(KeyCode::R) {
+        let replay = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+        new_game_ewr.send(NewGame { replay });
+    }
+}
+
+// 将主窗口中鼠标的位置转换为世界坐标，鼠标不在窗口中时返回None
+// 有多个相机时使用渲染到主窗口、视口包含鼠标并且priority最高的相机
+pub(crate) fn cursor_world_position(
+    window: &Window,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    cameras
+        .iter()
+        .filter(|(camera, _)| {
+            camera.is_active && camera.target == RenderTarget::Window(WindowId::primary())
+        })
+        .filter_map(|(camera, transform)| {
+            // 视口的位置以左上角为原点，鼠标的位置以左下角为原点
+            let (min, max) = camera.logical_viewport_rect()?;
+            let viewport_pos = Vec2::new(cursor.x - min.x, cursor.y - (window.height() - max.y));
+            let inside =
+                viewport_pos.cmpge(Vec2::ZERO).all() && viewport_pos.cmple(max - min).all();
+            inside.then_some((camera.priority, camera, transform, viewport_pos))
+        })
+        .max_by_key(|(priority, ..)| *priority)
+        .and_then(|(_, camera, transform, viewport_pos)| {
+            camera.viewport_to_world(transform, viewport_pos)
+        })
+        .map(|ray| ray.origin.truncate())
+}
@@ -0,0 +1,144 @@
+use bevy::{log, prelude::*};
+use minesweeper_core::{Game, TileState};
+
+use crate::{
+    components::{cursor::TileCursor, Coordinates},
+    events::{TileChordEvent, TileMarkEvent, TileTriggerEvent},
+    resources::{board::Board, replay::ReplayPlayback},
+};
+
+const CURSOR_COLOR: Color = Color::rgba(1., 0.9, 0.2, 0.45);
+
+// 键盘操作：方向键/hjkl移动光标，Space翻开，F插旗，Enter双击翻开
+// Tab跳到下一个与已翻开tile相邻的未翻开tile，Shift+Tab跳到上一个
+// 光标在第一次使用键盘时出现，回放录像时不响应
+// Shift+方向键用于移动视角
+#[allow(clippy::too_many_arguments)]
+pub fn keyboard_input(
+    mut commands: Commands,
+    board: Res<Board>,
+    keys: Res<Input<KeyCode>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
+    mut cursors: Query<(&Parent, &mut Transform), With<TileCursor>>,
+    mut position: Local<Option<Coordinates>>,
+) {
+    // Ctrl组合键用于撤销、保存录像等操作
+    if playback.is_some() || keys.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let arrow = |code: KeyCode| !shift && keys.just_pressed(code);
+    let (width, height) = (board.game.width(), board.game.height());
+    let current = position
+        .filter(|c| c.x < width && c.y < height)
+        .unwrap_or(Coordinates {
+            x: width / 2,
+            y: height / 2,
+        });
+
+    let mut coords = current;
+    if arrow(KeyCode::Left) || keys.just_pressed(KeyCode::H) {
+        coords.x = coords.x.saturating_sub(1);
+    }
+    if arrow(KeyCode::Right) || keys.just_pressed(KeyCode::L) {
+        coords.x = (coords.x + 1).min(width - 1);
+    }
+    // board的y轴向上
+    if arrow(KeyCode::Up) || keys.just_pressed(KeyCode::K) {
+        coords.y = (coords.y + 1).min(height - 1);
+    }
+    if arrow(KeyCode::Down) || keys.just_pressed(KeyCode::J) {
+        coords.y = coords.y.saturating_sub(1);
+    }
+    if keys.just_pressed(KeyCode::Tab) {
+        match next_frontier(&board.game, coords, shift) {
+            Some(next) => coords = next,
+            None => log::info!("No unrevealed tile next to a revealed one"),
+        }
+    }
+
+    let actions = [KeyCode::Space, KeyCode::F, KeyCode::Return];
+    let moved = coords != current || keys.just_pressed(KeyCode::Tab);
+    if !moved && !keys.any_just_pressed(actions) {
+        return;
+    }
+    *position = Some(coords);
+
+    // 第一次使用键盘或者重新开始游戏后创建光标
+    let translation = Vec3::new(
+        (coords.x as f32 + 0.5) * board.layout_tile_size,
+        (coords.y as f32 + 0.5) * board.layout_tile_size,
+        10.,
+    );
+    match cursors
+        .iter_mut()
+        .find(|(parent, _)| parent.get() == board.entity)
+    {
+        Some((_, mut transform)) => transform.translation = translation,
+        None => {
+            commands.entity(board.entity).with_children(|parent| {
+                parent
+                    .spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: CURSOR_COLOR,
+                            custom_size: Some(Vec2::splat(board.layout_tile_size)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(translation),
+                        ..default()
+                    })
+                    .insert(Name::new("Tile Cursor"))
+                    .insert(TileCursor);
+            });
+        }
+    }
+
+    // 游戏结束后只能移动光标
+    if board.game.is_over() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Space) {
+        log::info!("Trying to uncover tile on {}", coords);
+        tile_trigger_ewr.send(TileTriggerEvent(coords));
+    } else if keys.just_pressed(KeyCode::F) {
+        log::info!("Trying to mark tile on {}", coords);
+        tile_mark_ewr.send(TileMarkEvent(coords));
+    } else if keys.just_pressed(KeyCode::Return) {
+        log::info!("Trying to chord tile on {}", coords);
+        tile_chord_ewr.send(TileChordEvent(coords));
+    }
+}
+
+// 按从上到下、从左到右的顺序寻找from之后（backwards时为之前）的边界tile，到达末尾时从头开始
+// 边界tile为未翻开、未插旗并且与已翻开的tile相邻的tile
+fn next_frontier(game: &Game, from: Coordinates, backwards: bool) -> Option<Coordinates> {
+    let (width, height) = (game.width() as usize, game.height() as usize);
+    let tiles = width * height;
+    // 阅读顺序中的下标，第一行为y最大的一行
+    let index = |c: Coordinates| (height - 1 - c.y as usize) * width + c.x as usize;
+    let coords = |i: usize| Coordinates {
+        x: (i % width) as u16,
+        y: (height - 1 - i / width) as u16,
+    };
+    let is_frontier = |c: Coordinates| {
+        game.state_at(c) == Some(TileState::Covered)
+            && game
+                .tile_map()
+                .safe_square_at(c)
+                .any(|n| n != c && game.state_at(n) == Some(TileState::Uncovered))
+    };
+    let start = index(from);
+    (1..=tiles)
+        .map(|offset| {
+            if backwards {
+                (start + tiles - offset) % tiles
+            } else {
+                (start + offset) % tiles
+            }
+        })
+        .map(coords)
+        .find(|c| is_frontier(*c))
+}
@@ -0,0 +1,43 @@
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    window::{WindowId, WindowResized},
+};
+
+use crate::{
+    board_layout,
+    bounds::Bounds2,
+    resources::{board::Board, BoardOptions},
+};
+
+// 窗口大小改变时重新计算tile的大小和board的位置
+// tile仍按创建时的大小排列，通过缩放board实体显示为新的大小
+pub fn resize_board(
+    mut resized_evr: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    board_options: Res<BoardOptions>,
+    board: Option<ResMut<Board>>,
+    mut transforms: Query<&mut Transform>,
+) {
+    if !resized_evr.iter().any(|e| e.id == WindowId::primary()) {
+        return;
+    }
+    let Some(mut board) = board else {
+        return;
+    };
+    let Some(window) = windows.get_primary() else {
+        return;
+    };
+    let Ok(mut transform) = transforms.get_mut(board.entity) else {
+        return;
+    };
+    let map_size = (board.game.width(), board.game.height());
+    let (tile_size, position) = board_layout(&board_options, window, map_size);
+    transform.scale = Vec3::splat(tile_size / board.layout_tile_size);
+    transform.translation = position;
+    board.tile_size = tile_size;
+    board.bounds = Bounds2 {
+        position: position.xy(),
+        size: Vec2::new(tile_size * map_size.0 as f32, tile_size * map_size.1 as f32),
+    };
+}
@@ -0,0 +1,47 @@
+use bevy::{
+    log,
+    prelude::{
+        AssetServer, BuildChildren, Commands, DespawnRecursiveExt, EventReader, Res, ResMut,
+    },
+};
+
+use crate::{
+    events::TileMarkEvent,
+    resources::{board::Board, BoardOptions},
+    spawn_flag,
+};
+
+// 切换tile的标记状态，标记时在覆盖层上添加旗帜
+pub fn mark_tiles(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Res<BoardOptions>,
+    asset_server: Res<AssetServer>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    for event in tile_mark_evr.iter() {
+        let Some(mark) = board.game.toggle_flag(event.0) else {
+            continue;
+        };
+        let Some(&entity) = board.cover_entity(&event.0) else {
+            continue;
+        };
+        if mark {
+            commands.entity(entity).with_children(|parent| {
+                spawn_flag(
+                    parent,
+                    asset_server.load("sprites/flag.png"),
+                    board.layout_tile_size,
+                    board_options.tile_padding,
+                )
+            });
+        } else {
+            commands.entity(entity).despawn_descendants();
+        }
+        log::info!(
+            "Remaining mines: {} ({} flags)",
+            board.remaining_mines(),
+            board.flagged_count()
+        );
+    }
+}
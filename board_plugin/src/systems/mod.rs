@@ -1 +1,12 @@
-pub mod input;
+pub mod camera;
+pub mod hint;
+pub mod history;
+pub mod hud;
+pub mod input;
+pub mod keyboard;
+pub mod layout;
+pub mod mark;
+pub mod probability;
+pub mod replay;
+pub mod save;
+pub mod uncover;
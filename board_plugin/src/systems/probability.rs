@@ -0,0 +1,61 @@
+use bevy::{
+    log,
+    prelude::{Color, Entity, Input, KeyCode, Local, Query, Res, ResMut, Sprite},
+};
+
+use crate::resources::{board::Board, ProbabilityOverlay};
+
+// P键开关炸弹概率覆盖层
+pub fn toggle_probability_overlay(
+    keys: Res<Input<KeyCode>>,
+    mut overlay: ResMut<ProbabilityOverlay>,
+) {
+    if keys.just_pressed(KeyCode::P) {
+        overlay.enabled = !overlay.enabled;
+        log::info!("Probability overlay: {}", overlay.enabled);
+    }
+}
+
+// 按照炸弹概率为覆盖层着色：绿色安全，红色一定是炸弹
+// 只在board的状态变化时重新计算
+pub fn probability_overlay(
+    board: Res<Board>,
+    overlay: Res<ProbabilityOverlay>,
+    mut sprites: Query<&mut Sprite>,
+    mut last: Local<Option<(Entity, usize, usize, bool)>>,
+) {
+    // 游戏结束后保留结束时的着色
+    if board.game.is_over() {
+        return;
+    }
+    let key = (
+        board.entity,
+        board.game.covered_count(),
+        board.game.flagged_count(),
+        overlay.enabled,
+    );
+    if *last == Some(key) {
+        return;
+    }
+    *last = Some(key);
+
+    let probabilities = overlay.enabled.then(|| board.game.mine_probabilities());
+    if let Some(probabilities) = probabilities.as_ref() {
+        if !probabilities.is_exact() {
+            log::info!("Probabilities are sampled for large frontiers");
+        }
+    }
+    for (coords, entity) in board.covered_tiles.iter() {
+        let Ok(mut sprite) = sprites.get_mut(*entity) else {
+            continue;
+        };
+        let p = probabilities.as_ref().and_then(|p| p.get(*coords));
+        sprite.color = match p {
+            Some(p) => {
+                let p = p as f32;
+                Color::rgb(0.2 + 0.5 * p, 0.7 - 0.5 * p, 0.2)
+            }
+            None => Color::DARK_GRAY,
+        };
+    }
+}
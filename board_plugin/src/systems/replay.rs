@@ -0,0 +1,131 @@
+use bevy::{
+    log,
+    prelude::{EventReader, EventWriter, Input, KeyCode, Res, ResMut},
+    time::Time,
+};
+
+use crate::{
+    events::{HintEvent, Redo, TileChordEvent, TileMarkEvent, TileTriggerEvent, Undo},
+    resources::{
+        board::Board,
+        replay::{
+            ReplayButton, ReplayEvent, ReplayInput, ReplayPlayback, ReplayRecorder, REPLAY_DIR,
+        },
+    },
+};
+
+// 记录玩家的每次输入，时间为游戏用时
+// 同一帧内的输入按处理的顺序记录，见ReplayInput::order
+#[allow(clippy::too_many_arguments)]
+pub fn record_inputs(
+    board: Res<Board>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+    mut undo_evr: EventReader<Undo>,
+    mut redo_evr: EventReader<Redo>,
+    mut hint_evr: EventReader<HintEvent>,
+) {
+    // 继续旧版本存档的游戏时不录像
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    let click = |coords, button| ReplayInput::Click { coords, button };
+    let inputs = tile_trigger_evr
+        .iter()
+        .map(|e| click(e.0, ReplayButton::Left))
+        .chain(
+            tile_chord_evr
+                .iter()
+                .map(|e| click(e.0, ReplayButton::Chord)),
+        )
+        .chain(
+            tile_mark_evr
+                .iter()
+                .map(|e| click(e.0, ReplayButton::Right)),
+        )
+        .chain(undo_evr.iter().map(|_| ReplayInput::Undo))
+        .chain(redo_evr.iter().map(|_| ReplayInput::Redo))
+        .chain(hint_evr.iter().map(|_| ReplayInput::Hint));
+    let offset = board.clock.elapsed();
+    for input in inputs {
+        recorder.0.events.push(ReplayEvent { offset, input });
+    }
+}
+
+// Ctrl+S将当前这局的录像保存到录像目录
+pub fn save_replay(
+    keys: Res<Input<KeyCode>>,
+    board: Res<Board>,
+    recorder: Option<Res<ReplayRecorder>>,
+) {
+    if !(keys.just_pressed(KeyCode::S) && keys.any_pressed([KeyCode::LControl, KeyCode::RControl]))
+    {
+        return;
+    }
+    let Some(recorder) = recorder else {
+        log::warn!("This game was resumed from a save without a replay, no replay is available");
+        return;
+    };
+    let mut replay = recorder.0.clone();
+    if board.game.mines_placed() {
+        replay.tile_map = Some(board.game.tile_map().clone());
+    }
+    match replay.save_to_dir(REPLAY_DIR) {
+        Ok(path) => log::info!("Replay saved to {}", path.display()),
+        Err(e) => log::error!("Failed to save replay: {}", e),
+    }
+}
+
+// 按照录像的时间发送输入事件
+// 空格暂停/继续，右方向键单步，上下方向键调整回放速度
+#[allow(clippy::too_many_arguments)]
+pub fn play_replay(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
+    mut undo_ewr: EventWriter<Undo>,
+    mut redo_ewr: EventWriter<Redo>,
+    mut hint_ewr: EventWriter<HintEvent>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::Space) {
+        playback.paused = !playback.paused;
+        log::info!("Replay paused: {}", playback.paused);
+    }
+    if keys.just_pressed(KeyCode::Up) {
+        playback.speed = (playback.speed * 2.).min(ReplayPlayback::MAX_SPEED);
+        log::info!("Replay speed: {}x", playback.speed);
+    }
+    if keys.just_pressed(KeyCode::Down) {
+        playback.speed = (playback.speed / 2.).max(ReplayPlayback::MIN_SPEED);
+        log::info!("Replay speed: {}x", playback.speed);
+    }
+
+    let inputs = if keys.just_pressed(KeyCode::Right) {
+        playback.step().into_iter().collect()
+    } else {
+        playback.advance(time.delta())
+    };
+    for input in inputs {
+        match input {
+            ReplayInput::Click { coords, button } => match button {
+                ReplayButton::Left => tile_trigger_ewr.send(TileTriggerEvent(coords)),
+                ReplayButton::Right => tile_mark_ewr.send(TileMarkEvent(coords)),
+                ReplayButton::Chord => tile_chord_ewr.send(TileChordEvent(coords)),
+            },
+            ReplayInput::Undo => undo_ewr.send(Undo),
+            ReplayInput::Redo => redo_ewr.send(Redo),
+            ReplayInput::Hint => hint_ewr.send(HintEvent),
+        }
+        if playback.is_finished() {
+            log::info!("Replay finished");
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+
+use bevy::{
+    app::AppExit,
+    log,
+    prelude::{EventReader, Local, Res},
+    window::WindowCloseRequested,
+};
+
+use crate::resources::{
+    board::Board,
+    replay::{ReplayPlayback, ReplayRecorder},
+    save::SavedGame,
+    BoardOptions,
+};
+
+// 退出时保存进行中的游戏；没有进行中的游戏时删除旧的存档，避免下次启动继续已经结束的游戏
+pub fn autosave_on_exit(
+    board: Option<Res<Board>>,
+    board_options: Res<BoardOptions>,
+    playback: Option<Res<ReplayPlayback>>,
+    recorder: Option<Res<ReplayRecorder>>,
+    mut close_evr: EventReader<WindowCloseRequested>,
+    mut exit_evr: EventReader<AppExit>,
+    mut saved: Local<bool>,
+) {
+    let exiting = close_evr.iter().count() + exit_evr.iter().count() > 0;
+    if !exiting || *saved || !board_options.autosave || playback.is_some() {
+        return;
+    }
+    *saved = true;
+    let Some(path) = SavedGame::default_path() else {
+        log::warn!("No user data directory, the game is not saved");
+        return;
+    };
+
+    let in_progress = board
+        .as_ref()
+        .filter(|board| board.game.mines_placed() && !board.game.is_over());
+    match in_progress {
+        Some(board) => {
+            // 继续旧版本存档的游戏时没有完整的录像，存档中也不保存录像
+            let saved = SavedGame::new(
+                board_options.clone(),
+                board.game.clone(),
+                board.clock.elapsed(),
+                recorder.map(|recorder| recorder.0.clone()),
+            );
+            match saved.save(&path) {
+                Ok(()) => log::info!("Game saved to {}", path.display()),
+                Err(e) => log::error!("Failed to save game to {}: {}", path.display(), e),
+            }
+        }
+        None => {
+            if path.exists() {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::error!("Failed to remove old save {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+}
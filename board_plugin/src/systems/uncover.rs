@@ -0,0 +1,185 @@
+use bevy::{
+    log,
+    prelude::{
+        AssetServer, Color, Commands, DespawnRecursiveExt, Entity, EventReader, EventWriter, Query,
+        Res, ResMut, Sprite, With,
+    },
+};
+use minesweeper_core::{GameStatus, Reveal};
+
+use crate::{
+    components::{Coordinates, Uncover},
+    events::{GameLost, GameWon, TileChordEvent, TileTriggerEvent},
+    resources::{board::Board, BoardOptions},
+    spawn_tile_content,
+};
+
+// 翻开被点击的tile
+pub fn trigger_event_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_options: Res<BoardOptions>,
+    asset_server: Res<AssetServer>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut game_won_ewr: EventWriter<GameWon>,
+    mut game_lost_ewr: EventWriter<GameLost>,
+) {
+    for TileTriggerEvent(coords) in tile_trigger_evr.iter() {
+        let reveal = board.game.reveal(*coords);
+        if reveal.mines_placed {
+            // 安全开局：炸弹在第一次翻开时才布置，需要补上每个tile的内容
+            #[cfg(feature = "debug")]
+            log::info!("{}", board.game.tile_map().console_output());
+
+            let font = asset_server.load("fonts/pixeled.ttf");
+            let bomb_png = asset_server.load("sprites/bomb.png");
+            for (y, line) in board.game.tile_map().iter().enumerate() {
+                for (x, tile) in line.iter().enumerate() {
+                    let coords = Coordinates {
+                        x: x as u16,
+                        y: y as u16,
+                    };
+                    let Some(entity) = board.tile_entity(&coords) else {
+                        continue;
+                    };
+                    spawn_tile_content(
+                        &mut commands.entity(*entity),
+                        tile,
+                        board.layout_tile_size,
+                        board_options.tile_padding,
+                        font.clone(),
+                        bomb_png.clone(),
+                    );
+                }
+            }
+        }
+        apply_reveal(
+            &mut commands,
+            &mut board,
+            &reveal,
+            &mut game_won_ewr,
+            &mut game_lost_ewr,
+        );
+    }
+}
+
+// 双击已翻开的数字块：周围插旗数与数字相同时翻开其余未标记的相邻块
+pub fn chord_event_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+    mut game_won_ewr: EventWriter<GameWon>,
+    mut game_lost_ewr: EventWriter<GameLost>,
+) {
+    for TileChordEvent(coords) in tile_chord_evr.iter() {
+        let reveal = board.game.chord(*coords);
+        apply_reveal(
+            &mut commands,
+            &mut board,
+            &reveal,
+            &mut game_won_ewr,
+            &mut game_lost_ewr,
+        );
+    }
+}
+
+// 为翻开的tile的覆盖层添加Uncover组件，游戏结束时发送对应的事件
+fn apply_reveal(
+    commands: &mut Commands,
+    board: &mut Board,
+    reveal: &Reveal,
+    game_won_ewr: &mut EventWriter<GameWon>,
+    game_lost_ewr: &mut EventWriter<GameLost>,
+) {
+    if reveal.uncovered.is_empty() {
+        return;
+    }
+    for coords in reveal.uncovered.iter() {
+        if let Some(entity) = board.covered_tiles.remove(coords) {
+            commands.entity(entity).insert(Uncover);
+        }
+    }
+    handle_status(board, game_won_ewr, game_lost_ewr);
+}
+
+// 根据游戏状态开始或停止计时，游戏结束时发送对应的事件
+// 还没有翻开任何tile（例如撤销了第一次翻开）时不开始计时
+pub(crate) fn handle_status(
+    board: &mut Board,
+    game_won_ewr: &mut EventWriter<GameWon>,
+    game_lost_ewr: &mut EventWriter<GameLost>,
+) {
+    let tile_count = board.game.width() as usize * board.game.height() as usize;
+    match board.game.status() {
+        GameStatus::Playing => {
+            if board.clock.paused() && board.game.covered_count() < tile_count {
+                board.clock.unpause();
+            }
+        }
+        GameStatus::Won => {
+            let (hints, assisted) = (board.game.hints(), board.game.is_assisted());
+            log::info!(
+                "Board completed ! ({} hints, assisted: {})",
+                hints,
+                assisted
+            );
+            board.clock.pause();
+            game_won_ewr.send(GameWon { hints, assisted });
+        }
+        GameStatus::Lost { at } => {
+            let (hints, assisted) = (board.game.hints(), board.game.is_assisted());
+            log::info!("Boom ! ({} hints, assisted: {})", hints, assisted);
+            board.clock.pause();
+            game_lost_ewr.send(GameLost {
+                at,
+                hints,
+                assisted,
+            });
+        }
+    }
+}
+
+// 移除带有Uncover组件的覆盖层
+pub fn uncover_tiles(mut commands: Commands, covers: Query<Entity, With<Uncover>>) {
+    for entity in covers.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// 游戏失败时翻开所有炸弹，并标出插错的旗帜
+pub fn reveal_on_loss(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut sprites: Query<&mut Sprite>,
+    mut game_lost_evr: EventReader<GameLost>,
+) {
+    let Some(event) = game_lost_evr.iter().last() else {
+        return;
+    };
+    // 踩中的炸弹用红色背景标出
+    if let Some(entity) = board.tile_entity(&event.at) {
+        if let Ok(mut sprite) = sprites.get_mut(*entity) {
+            sprite.color = Color::RED;
+        }
+    }
+
+    // 插对旗帜的炸弹保持覆盖
+    let mines: Vec<Coordinates> = board
+        .game
+        .mines()
+        .filter(|coords| !board.game.is_flagged(*coords))
+        .collect();
+    for coords in mines {
+        if let Some(entity) = board.covered_tiles.remove(&coords) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for coords in board.game.wrong_flags() {
+        let Some(entity) = board.covered_tiles.get(&coords) else {
+            continue;
+        };
+        if let Ok(mut sprite) = sprites.get_mut(*entity) {
+            sprite.color = Color::ORANGE_RED;
+        }
+    }
+}
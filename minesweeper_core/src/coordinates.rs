@@ -3,10 +3,12 @@ use std::{
     ops::{Add, Sub},
 };
 
-use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
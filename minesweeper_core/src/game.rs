@@ -0,0 +1,642 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coordinates::Coordinates,
+    generation::{no_guess_tile_map, Generation},
+    hint::{self, Hint},
+    probability::{self, Probabilities},
+    solver::{self, Deduction, Visible},
+    tile::Tile,
+    tile_map::TileMap,
+};
+
+// 安全开局的策略
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SafeStartPolicy {
+    #[default]
+    SafeCell, // 第一次翻开的tile不是炸弹
+    Opening, // 第一次翻开的tile及其周围8个tile都不是炸弹，保证能翻开一片区域
+}
+
+// 玩家看到的每个tile的状态
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TileState {
+    #[default]
+    Covered, // 未翻开
+    Flagged,   // 未翻开且被插旗
+    Uncovered, // 已翻开
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost { at: Coordinates }, // at为被翻开的炸弹的坐标
+}
+
+// 一次翻开操作的结果
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Reveal {
+    pub uncovered: Vec<Coordinates>, // 这次被翻开的所有tile，按翻开的顺序排列
+    pub mines_placed: bool,          // 这次操作是否触发了布雷（安全开局）
+}
+
+// 玩家的一次操作
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    Reveal(Coordinates),
+    Chord(Coordinates),
+    ToggleFlag(Coordinates),
+}
+
+// 历史记录中的一步操作以及撤销它所需的信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Step {
+    action: Action,
+    uncovered: Vec<Coordinates>, // 这次操作翻开的tile
+    status: GameStatus,          // 操作之前的游戏状态
+}
+
+// 一局扫雷的完整状态，包括炸弹布局以及玩家的操作结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Game {
+    tile_map: TileMap,
+    states: Vec<Vec<TileState>>,
+    bomb_count: u16,
+    seed: u64,
+    safe_start: Option<SafeStartPolicy>,
+    generation: Generation,
+    mines_placed: bool,
+    covered_count: usize,
+    flagged_count: usize,
+    status: GameStatus,
+    #[serde(default)]
+    hints: u32, // 使用提示的次数，用于在统计中区分
+    #[serde(default)]
+    history: Vec<Step>, // 可以撤销的操作
+    #[serde(default)]
+    undone: Vec<Action>, // 已撤销、可以重做的操作
+    #[serde(default)]
+    assisted: bool, // 是否使用过撤销
+}
+
+impl Game {
+    // 创建一局新游戏，safe_start为None时立即布置炸弹，否则推迟到第一次翻开时
+    // 无猜布局需要知道第一次翻开的位置，未设置safe_start时按照Opening处理
+    pub fn new(
+        width: u16,
+        height: u16,
+        bomb_count: u16,
+        seed: u64,
+        safe_start: Option<SafeStartPolicy>,
+        generation: Generation,
+    ) -> Self {
+        let safe_start = match generation {
+            Generation::Random => safe_start,
+            Generation::NoGuess { .. } => safe_start.or(Some(SafeStartPolicy::Opening)),
+        };
+        let mut tile_map = TileMap::new(width, height);
+        let mines_placed = safe_start.is_none();
+        if mines_placed {
+            tile_map.set_bombs(bomb_count, seed);
+        }
+        let mut game = Self::with_tile_map(tile_map, seed, safe_start);
+        game.generation = generation;
+        game.mines_placed = mines_placed;
+        game.bomb_count = if mines_placed {
+            game.tile_map.bomb_count()
+        } else {
+            bomb_count
+        };
+        game
+    }
+
+    // 使用已经布置好炸弹的地图创建一局游戏，用于重玩同一布局
+    pub fn from_tile_map(tile_map: TileMap) -> Self {
+        let seed = tile_map.seed();
+        Self::with_tile_map(tile_map, seed, None)
+    }
+
+    fn with_tile_map(tile_map: TileMap, seed: u64, safe_start: Option<SafeStartPolicy>) -> Self {
+        let (width, height) = (tile_map.width(), tile_map.height());
+        Self {
+            states: vec![vec![TileState::Covered; width as usize]; height as usize],
+            bomb_count: tile_map.bomb_count(),
+            seed,
+            safe_start,
+            generation: Generation::Random,
+            mines_placed: true,
+            covered_count: width as usize * height as usize,
+            flagged_count: 0,
+            status: GameStatus::Playing,
+            hints: 0,
+            history: Vec::new(),
+            undone: Vec::new(),
+            assisted: false,
+            tile_map,
+        }
+    }
+
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    pub fn width(&self) -> u16 {
+        self.tile_map.width()
+    }
+
+    pub fn height(&self) -> u16 {
+        self.tile_map.height()
+    }
+
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn generation(&self) -> Generation {
+        self.generation
+    }
+
+    pub fn mines_placed(&self) -> bool {
+        self.mines_placed
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.status != GameStatus::Playing
+    }
+
+    pub fn hints(&self) -> u32 {
+        self.hints
+    }
+
+    // 使用过撤销的游戏不计入记录
+    pub fn is_assisted(&self) -> bool {
+        self.assisted
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub fn state_at(&self, coords: Coordinates) -> Option<TileState> {
+        if !self.tile_map.contains(coords) {
+            return None;
+        }
+        Some(self.states[coords.y as usize][coords.x as usize])
+    }
+
+    // 未翻开（包括插旗）的tile
+    pub fn is_covered(&self, coords: Coordinates) -> bool {
+        matches!(
+            self.state_at(coords),
+            Some(TileState::Covered | TileState::Flagged)
+        )
+    }
+
+    pub fn is_flagged(&self, coords: Coordinates) -> bool {
+        self.state_at(coords) == Some(TileState::Flagged)
+    }
+
+    pub fn covered_count(&self) -> usize {
+        self.covered_count
+    }
+
+    pub fn flagged_count(&self) -> usize {
+        self.flagged_count
+    }
+
+    // 剩余雷数，即炸弹总数减去插旗数，插旗过多时为负数
+    pub fn remaining_mines(&self) -> i32 {
+        self.bomb_count as i32 - self.flagged_count as i32
+    }
+
+    // 某个坐标周围插旗的数量
+    pub fn adjacent_flag_count(&self, coords: Coordinates) -> u8 {
+        self.tile_map
+            .safe_square_at(coords)
+            .filter(|c| self.is_flagged(*c))
+            .count() as u8
+    }
+
+    // 所有炸弹的坐标
+    pub fn mines(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.coordinates()
+            .filter(|coords| self.tile_map.is_bombs_at(*coords))
+    }
+
+    // 所有插旗的坐标
+    pub fn flags(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.coordinates().filter(|coords| self.is_flagged(*coords))
+    }
+
+    // 插错旗（旗下不是炸弹）的坐标
+    pub fn wrong_flags(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.flags()
+            .filter(|coords| !self.tile_map.is_bombs_at(*coords))
+    }
+
+    // 根据玩家当前能看到的信息推导出一定安全以及一定是炸弹的tile
+    pub fn deduce(&self) -> Deduction {
+        solver::deduce(&Visible::from_game(self))
+    }
+
+    // 请求一次提示，找到可以翻开的tile时计入提示次数
+    pub fn request_hint(&mut self) -> Option<Hint> {
+        let hint = hint::hint(self)?;
+        self.hints += 1;
+        Some(hint)
+    }
+
+    // 每个未翻开的tile是炸弹的概率，炸弹还未布置时为空
+    pub fn mine_probabilities(&self) -> Probabilities {
+        probability::probabilities(&Visible::from_game(self), self.seed)
+    }
+
+    fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let (width, height) = (self.width(), self.height());
+        (0..height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+    }
+}
+
+// 玩家的操作
+impl Game {
+    // 翻开某个tile，空白块会连带翻开周围相连的空白块及其边缘的数字块
+    // 已翻开、插旗或者游戏已经结束时不做任何处理
+    pub fn reveal(&mut self, coords: Coordinates) -> Reveal {
+        let status = self.status;
+        let reveal = self.apply_reveal(coords);
+        self.record(Action::Reveal(coords), &reveal, status);
+        reveal
+    }
+
+    // 双击已翻开的数字块：周围插旗数与数字相同时翻开其余未标记的相邻块
+    // 若旗帜插错，会翻开炸弹导致失败
+    pub fn chord(&mut self, coords: Coordinates) -> Reveal {
+        let status = self.status;
+        let reveal = self.apply_chord(coords);
+        self.record(Action::Chord(coords), &reveal, status);
+        reveal
+    }
+
+    // 切换插旗状态，返回切换后是否插旗，已翻开的tile或游戏结束时返回None
+    pub fn toggle_flag(&mut self, coords: Coordinates) -> Option<bool> {
+        let status = self.status;
+        let flagged = self.apply_toggle_flag(coords)?;
+        self.record(Action::ToggleFlag(coords), &Reveal::default(), status);
+        Some(flagged)
+    }
+
+    // 撤销最近的一次操作，返回被撤销的操作
+    // 炸弹的布局不会被撤销，即使撤销的是第一次翻开
+    pub fn undo(&mut self) -> Option<Action> {
+        let step = self.history.pop()?;
+        for coords in step.uncovered.iter() {
+            self.states[coords.y as usize][coords.x as usize] = TileState::Covered;
+            self.covered_count += 1;
+        }
+        self.status = step.status;
+        if let Action::ToggleFlag(coords) = step.action {
+            self.apply_toggle_flag(coords);
+        }
+        self.assisted = true;
+        self.undone.push(step.action);
+        Some(step.action)
+    }
+
+    // 重做最近一次被撤销的操作
+    pub fn redo(&mut self) -> Option<Action> {
+        let action = self.undone.pop()?;
+        let status = self.status;
+        let reveal = match action {
+            Action::Reveal(coords) => self.apply_reveal(coords),
+            Action::Chord(coords) => self.apply_chord(coords),
+            Action::ToggleFlag(coords) => {
+                self.apply_toggle_flag(coords);
+                Reveal::default()
+            }
+        };
+        self.history.push(Step {
+            action,
+            uncovered: reveal.uncovered,
+            status,
+        });
+        Some(action)
+    }
+
+    // 记录一次改变了状态的操作，新的操作会清空重做记录
+    fn record(&mut self, action: Action, reveal: &Reveal, status: GameStatus) {
+        if reveal.uncovered.is_empty() && !matches!(action, Action::ToggleFlag(_)) {
+            return;
+        }
+        self.history.push(Step {
+            action,
+            uncovered: reveal.uncovered.clone(),
+            status,
+        });
+        self.undone.clear();
+    }
+
+    fn apply_reveal(&mut self, coords: Coordinates) -> Reveal {
+        let mut reveal = Reveal::default();
+        if self.is_over() || self.state_at(coords) != Some(TileState::Covered) {
+            return reveal;
+        }
+        if !self.mines_placed {
+            self.place_mines(coords);
+            reveal.mines_placed = true;
+        }
+        self.flood_reveal(coords, &mut reveal.uncovered);
+        self.update_status();
+        reveal
+    }
+
+    fn apply_chord(&mut self, coords: Coordinates) -> Reveal {
+        let mut reveal = Reveal::default();
+        if self.is_over() || self.state_at(coords) != Some(TileState::Uncovered) {
+            return reveal;
+        }
+        let Some(Tile::BombNeighbor(count)) = self.tile_map.tile_at(coords) else {
+            return reveal;
+        };
+        if self.adjacent_flag_count(coords) != count {
+            return reveal;
+        }
+        let neighbors: Vec<Coordinates> = self.tile_map.safe_square_at(coords).collect();
+        for neighbor in neighbors {
+            if self.state_at(neighbor) == Some(TileState::Covered) {
+                self.flood_reveal(neighbor, &mut reveal.uncovered);
+            }
+        }
+        self.update_status();
+        reveal
+    }
+
+    fn apply_toggle_flag(&mut self, coords: Coordinates) -> Option<bool> {
+        if self.is_over() {
+            return None;
+        }
+        let state = self.state_at(coords)?;
+        let (state, flagged) = match state {
+            TileState::Covered => {
+                self.flagged_count += 1;
+                (TileState::Flagged, true)
+            }
+            TileState::Flagged => {
+                self.flagged_count -= 1;
+                (TileState::Covered, false)
+            }
+            TileState::Uncovered => return None,
+        };
+        self.states[coords.y as usize][coords.x as usize] = state;
+        Some(flagged)
+    }
+
+    // 按照安全开局的策略布置炸弹，保证first（以及周围）没有炸弹
+    fn place_mines(&mut self, first: Coordinates) {
+        let mut safe_zone = vec![first];
+        if self.safe_start == Some(SafeStartPolicy::Opening) {
+            let neighbors: Vec<Coordinates> = self
+                .tile_map
+                .safe_square_at(first)
+                .filter(|c| self.tile_map.contains(*c))
+                .collect();
+            let free = self.width() as usize * self.height() as usize - neighbors.len() - 1;
+            // 炸弹过多时无法保证翻开一片区域，退化为只保证当前tile安全
+            if free >= self.bomb_count as usize {
+                safe_zone.extend(neighbors);
+            }
+        }
+        match self.generation {
+            Generation::Random => {
+                self.tile_map
+                    .set_bombs_avoiding(self.bomb_count, &safe_zone, self.seed)
+            }
+            Generation::NoGuess {
+                max_attempts,
+                fallback,
+            } => {
+                self.tile_map = no_guess_tile_map(
+                    (self.width(), self.height()),
+                    self.bomb_count,
+                    &safe_zone,
+                    first,
+                    self.seed,
+                    max_attempts,
+                    fallback,
+                );
+            }
+        }
+        self.bomb_count = self.tile_map.bomb_count();
+        self.mines_placed = true;
+    }
+
+    fn flood_reveal(&mut self, start: Coordinates, uncovered: &mut Vec<Coordinates>) {
+        let mut queue = VecDeque::from([start]);
+        while let Some(coords) = queue.pop_front() {
+            if self.state_at(coords) != Some(TileState::Covered) {
+                continue;
+            }
+            self.states[coords.y as usize][coords.x as usize] = TileState::Uncovered;
+            self.covered_count -= 1;
+            uncovered.push(coords);
+            match self.tile_map.tile_at(coords) {
+                Some(Tile::Bomb) if self.status == GameStatus::Playing => {
+                    self.status = GameStatus::Lost { at: coords };
+                }
+                Some(Tile::Empty) => queue.extend(self.tile_map.safe_square_at(coords)),
+                _ => (),
+            }
+        }
+    }
+
+    // 剩下未翻开的tile都是炸弹时游戏胜利
+    fn update_status(&mut self) {
+        if self.status == GameStatus::Playing
+            && self.mines_placed
+            && self.covered_count == self.bomb_count as usize
+        {
+            self.status = GameStatus::Won;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords(x: u16, y: u16) -> Coordinates {
+        Coordinates { x, y }
+    }
+
+    // 5x5的地图，只有右上角(4, 4)一个炸弹
+    fn single_mine_game() -> Game {
+        let mut tile_map = TileMap::new(5, 5);
+        let corner = coords(4, 4);
+        let safe_zone: Vec<Coordinates> = (0..5)
+            .flat_map(|y| (0..5).map(move |x| coords(x, y)))
+            .filter(|c| *c != corner)
+            .collect();
+        tile_map.set_bombs_avoiding(1, &safe_zone, 0);
+        Game::from_tile_map(tile_map)
+    }
+
+    #[test]
+    fn reveal_empty_floods_to_numbers() {
+        let mut game = single_mine_game();
+        let reveal = game.reveal(coords(0, 0));
+
+        assert_eq!(reveal.uncovered.len(), 24);
+        assert!(game.is_covered(coords(4, 4)));
+        assert_eq!(game.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn reveal_bomb_loses() {
+        let mut game = single_mine_game();
+        game.reveal(coords(4, 4));
+
+        assert_eq!(game.status(), GameStatus::Lost { at: coords(4, 4) });
+        assert!(game.reveal(coords(0, 0)).uncovered.is_empty());
+    }
+
+    #[test]
+    fn flagged_tiles_are_not_revealed() {
+        let mut game = single_mine_game();
+        assert_eq!(game.toggle_flag(coords(3, 3)), Some(true));
+        assert!(game.reveal(coords(3, 3)).uncovered.is_empty());
+
+        game.reveal(coords(0, 0));
+        assert!(game.is_flagged(coords(3, 3)));
+        assert_eq!(game.remaining_mines(), 0);
+        assert_eq!(game.status(), GameStatus::Playing);
+
+        assert_eq!(game.toggle_flag(coords(3, 3)), Some(false));
+        assert_eq!(game.toggle_flag(coords(0, 0)), None);
+    }
+
+    #[test]
+    fn chord_with_wrong_flag_loses() {
+        let mut game = single_mine_game();
+        game.reveal(coords(3, 3));
+        game.toggle_flag(coords(3, 4));
+        game.chord(coords(3, 3));
+
+        assert_eq!(game.status(), GameStatus::Lost { at: coords(4, 4) });
+    }
+
+    #[test]
+    fn chord_with_correct_flag_uncovers_neighbors() {
+        let mut game = single_mine_game();
+        game.reveal(coords(3, 3));
+        game.toggle_flag(coords(4, 4));
+        let reveal = game.chord(coords(3, 3));
+
+        assert!(!reveal.uncovered.is_empty());
+        assert_eq!(game.status(), GameStatus::Won);
+    }
+
+    #[test]
+    fn undo_loss_and_redo() {
+        let mut game = single_mine_game();
+        game.reveal(coords(3, 3));
+        let covered = game.covered_count();
+        game.reveal(coords(4, 4));
+        assert!(game.is_over());
+        assert!(!game.is_assisted());
+
+        assert_eq!(game.undo(), Some(Action::Reveal(coords(4, 4))));
+        assert_eq!(game.status(), GameStatus::Playing);
+        assert_eq!(game.covered_count(), covered);
+        assert!(game.is_covered(coords(4, 4)));
+        assert!(game.is_assisted());
+
+        assert_eq!(game.redo(), Some(Action::Reveal(coords(4, 4))));
+        assert_eq!(game.status(), GameStatus::Lost { at: coords(4, 4) });
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn undo_flag_and_new_action_clears_redo() {
+        let mut game = single_mine_game();
+        game.reveal(coords(3, 3));
+        game.toggle_flag(coords(4, 4));
+        assert_eq!(game.undo(), Some(Action::ToggleFlag(coords(4, 4))));
+        assert!(!game.is_flagged(coords(4, 4)));
+        assert_eq!(game.flagged_count(), 0);
+        assert!(game.can_redo());
+
+        game.toggle_flag(coords(4, 3));
+        assert!(!game.can_redo());
+        assert_eq!(game.undo(), Some(Action::ToggleFlag(coords(4, 3))));
+        assert_eq!(game.undo(), Some(Action::Reveal(coords(3, 3))));
+        assert_eq!(game.covered_count(), 25);
+        assert_eq!(game.undo(), None);
+    }
+
+    #[test]
+    fn safe_start_opening() {
+        for seed in 0..20 {
+            let mut game = Game::new(
+                9,
+                9,
+                10,
+                seed,
+                Some(SafeStartPolicy::Opening),
+                Generation::Random,
+            );
+            assert!(!game.mines_placed());
+            let reveal = game.reveal(coords(4, 4));
+
+            assert!(reveal.mines_placed);
+            assert_eq!(game.tile_map().tile_at(coords(4, 4)), Some(Tile::Empty));
+            assert!(reveal.uncovered.len() >= 9);
+            assert_ne!(game.status(), GameStatus::Lost { at: coords(4, 4) });
+        }
+    }
+
+    #[test]
+    fn no_guess_generation_is_solvable() {
+        let first = coords(4, 4);
+        for seed in 0..5 {
+            let mut game = Game::new(9, 9, 10, seed, None, Generation::no_guess());
+            game.reveal(first);
+
+            assert!(crate::generation::solve_from(game.tile_map(), first).0);
+        }
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut game = Game::new(
+            9,
+            9,
+            10,
+            3,
+            Some(SafeStartPolicy::SafeCell),
+            Generation::Random,
+        );
+        game.reveal(coords(0, 0));
+        let flag = game.mines().next().unwrap();
+        game.toggle_flag(flag);
+
+        let json = serde_json::to_string(&game).unwrap();
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(**loaded.tile_map(), **game.tile_map());
+        assert_eq!(loaded.covered_count(), game.covered_count());
+        assert!(loaded.is_flagged(flag));
+    }
+}
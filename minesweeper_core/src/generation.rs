@@ -0,0 +1,93 @@
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    coordinates::Coordinates,
+    game::{Game, GameStatus},
+    solver::{deduce, Visible},
+    tile_map::TileMap,
+};
+
+// 炸弹布局的生成方式
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Generation {
+    #[default]
+    Random, // 完全随机，可能需要猜测
+    // 从第一次翻开的位置开始，只靠逻辑推导就能完成的布局
+    // 最多尝试max_attempts次，仍然失败时按照fallback处理
+    NoGuess {
+        max_attempts: u32,
+        fallback: NoGuessFallback,
+    },
+}
+
+// 无猜布局在尝试次数用完后的处理方式
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NoGuessFallback {
+    #[default]
+    MostSolved, // 使用能推导出最多tile的布局
+    LastAttempt, // 使用最后一次生成的布局
+}
+
+impl Generation {
+    pub const fn no_guess() -> Self {
+        Generation::NoGuess {
+            max_attempts: 500,
+            fallback: NoGuessFallback::MostSolved,
+        }
+    }
+}
+
+// 生成无猜布局，每次尝试使用由seed派生出的新种子
+// 返回的地图的seed为最终被采用的那次尝试的种子
+pub fn no_guess_tile_map(
+    (width, height): (u16, u16),
+    bomb_count: u16,
+    safe_zone: &[Coordinates],
+    first: Coordinates,
+    seed: u64,
+    max_attempts: u32,
+    fallback: NoGuessFallback,
+) -> TileMap {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut best: Option<(usize, TileMap)> = None;
+    for attempt in 0..max_attempts.max(1) {
+        let attempt_seed = if attempt == 0 { seed } else { rng.next_u64() };
+        let mut tile_map = TileMap::new(width, height);
+        tile_map.set_bombs_avoiding(bomb_count, safe_zone, attempt_seed);
+
+        let (solved, covered) = solve_from(&tile_map, first);
+        if solved {
+            return tile_map;
+        }
+        let better = match (&best, fallback) {
+            (_, NoGuessFallback::LastAttempt) | (None, _) => true,
+            (Some((best_covered, _)), NoGuessFallback::MostSolved) => covered < *best_covered,
+        };
+        if better {
+            best = Some((covered, tile_map));
+        }
+    }
+    best.map(|(_, tile_map)| tile_map)
+        .unwrap_or_else(|| TileMap::new(width, height))
+}
+
+// 从first开始只用逻辑推导玩这一局，返回是否获胜以及最后剩下的未翻开的数目
+pub fn solve_from(tile_map: &TileMap, first: Coordinates) -> (bool, usize) {
+    let mut game = Game::from_tile_map(tile_map.clone());
+    game.reveal(first);
+    while game.status() == GameStatus::Playing {
+        let deduction = deduce(&Visible::from_game(&game));
+        if deduction.is_empty() {
+            break;
+        }
+        for coords in deduction.mines {
+            game.toggle_flag(coords);
+        }
+        for coords in deduction.safe {
+            game.reveal(coords);
+        }
+    }
+    (game.status() == GameStatus::Won, game.covered_count())
+}
@@ -0,0 +1,284 @@
+use crate::{
+    coordinates::Coordinates,
+    game::Game,
+    probability,
+    solver::{self, Cell, Visible},
+};
+
+// 提示的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintKind {
+    Safe,                       // 可以证明一定不是炸弹
+    Guess { probability: f64 }, // 没有可以证明安全的tile，是炸弹概率最低的tile
+}
+
+// 一次提示：建议翻开的tile以及证明它安全的数字块
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub coords: Coordinates,
+    pub kind: HintKind,
+    pub reasons: Vec<Coordinates>, // 推导时用到的已翻开的数字块
+    pub mine_count: bool,          // 推导时还用到了剩余的炸弹数
+}
+
+// 寻找一个可以翻开的tile，炸弹还未布置或游戏已经结束时返回None
+// 插旗可能是错的，推导时只使用已翻开的数字，也不会提示插旗的tile
+pub fn hint(game: &Game) -> Option<Hint> {
+    if !game.mines_placed() || game.is_over() {
+        return None;
+    }
+    let visible = Visible::from_game(game).without_flags();
+    let deduction = solver::deduce(&visible);
+    let safe: Vec<_> = deduction
+        .safe
+        .into_iter()
+        .filter(|c| !game.is_flagged(*c))
+        .collect();
+
+    // 优先选择只需要附近一两个数字块就能证明的tile
+    for coords in safe.iter() {
+        if let Some(reasons) = simple_explanation(&visible, *coords) {
+            return Some(Hint {
+                coords: *coords,
+                kind: HintKind::Safe,
+                reasons,
+                mine_count: false,
+            });
+        }
+    }
+    if let Some(coords) = safe.first() {
+        let (reasons, mine_count) = explain(&visible, *coords);
+        return Some(Hint {
+            coords: *coords,
+            kind: HintKind::Safe,
+            reasons,
+            mine_count,
+        });
+    }
+
+    let (coords, probability) = probability::probabilities(&visible, game.seed())
+        .iter()
+        .filter(|(c, _)| !game.is_flagged(*c))
+        .min_by(|(a, p), (b, q)| p.total_cmp(q).then(a.cmp(b)))?;
+    Some(Hint {
+        coords,
+        kind: HintKind::Guess { probability },
+        reasons: numbers_near(&visible, coords, 1),
+        mine_count: false,
+    })
+}
+
+// 找出证明某个tile安全所需的数字块：依次尝试附近的一两个数字块、附近的所有数字块、
+// 所有数字块，都无法证明时再加上剩余的炸弹数，返回的bool表示是否用到了炸弹数
+fn explain(visible: &Visible, coords: Coordinates) -> (Vec<Coordinates>, bool) {
+    if let Some(reasons) = simple_explanation(visible, coords) {
+        return (reasons, false);
+    }
+    let near = numbers_near(visible, coords, 2);
+    let all: Vec<Coordinates> = visible
+        .coordinates()
+        .filter(|c| matches!(visible.cell(*c), Some(Cell::Revealed(n)) if n > 0))
+        .collect();
+    for total_mines in [None, visible.total_mines()] {
+        for numbers in [&near, &all] {
+            if proves_safe(visible, coords, numbers, total_mines) {
+                return (numbers.clone(), total_mines.is_some());
+            }
+        }
+    }
+    (all, visible.total_mines().is_some())
+}
+
+// 相邻的单个数字块，或者至少有一个相邻的两个附近的数字块
+fn simple_explanation(visible: &Visible, coords: Coordinates) -> Option<Vec<Coordinates>> {
+    let adjacent = numbers_near(visible, coords, 1);
+    if let Some(number) = adjacent
+        .iter()
+        .find(|number| proves_safe(visible, coords, &[**number], None))
+    {
+        return Some(vec![*number]);
+    }
+    let near = numbers_near(visible, coords, 2);
+    for (i, a) in near.iter().enumerate() {
+        for b in near.iter().skip(i + 1) {
+            if (adjacent.contains(a) || adjacent.contains(b))
+                && proves_safe(visible, coords, &[*a, *b], None)
+            {
+                return Some(vec![*a, *b]);
+            }
+        }
+    }
+    None
+}
+
+// 只保留numbers中的数字块（total_mines不为None时加上炸弹总数）时能否证明coords安全
+// 其余已翻开的tile视为已知安全但不提供数字，推导结果仍然成立
+fn proves_safe(
+    visible: &Visible,
+    coords: Coordinates,
+    numbers: &[Coordinates],
+    total_mines: Option<u16>,
+) -> bool {
+    let cells = (0..visible.height())
+        .map(|y| {
+            (0..visible.width())
+                .map(|x| {
+                    let c = Coordinates { x, y };
+                    match visible.cell(c) {
+                        Some(Cell::Revealed(_)) if !numbers.contains(&c) => Cell::Safe,
+                        Some(cell) => cell,
+                        None => Cell::Hidden,
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    solver::deduce(&Visible::new(cells, total_mines))
+        .safe
+        .contains(&coords)
+}
+
+// 与coords距离不超过distance的非零数字块
+fn numbers_near(visible: &Visible, coords: Coordinates, distance: u16) -> Vec<Coordinates> {
+    let range = |v: u16| v.saturating_sub(distance)..=v.saturating_add(distance);
+    range(coords.y)
+        .flat_map(|y| range(coords.x).map(move |x| Coordinates { x, y }))
+        .filter(|c| *c != coords)
+        .filter(|c| matches!(visible.cell(*c), Some(Cell::Revealed(n)) if n > 0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Generation, SafeStartPolicy, TileMap, TileState};
+
+    #[test]
+    fn hint_is_safe_and_explained() {
+        for seed in 0..20 {
+            let mut game = Game::new(
+                9,
+                9,
+                10,
+                seed,
+                Some(SafeStartPolicy::Opening),
+                Generation::Random,
+            );
+            assert!(hint(&game).is_none());
+            game.reveal(Coordinates { x: 4, y: 4 });
+            if game.is_over() {
+                continue;
+            }
+            let hint = hint(&game).unwrap();
+            assert_eq!(game.state_at(hint.coords), Some(TileState::Covered));
+            if hint.kind == HintKind::Safe {
+                assert!(!game.tile_map().is_bombs_at(hint.coords));
+                assert!(!hint.reasons.is_empty() || hint.mine_count);
+                assert!(hint
+                    .reasons
+                    .iter()
+                    .all(|c| game.state_at(*c) == Some(TileState::Uncovered)));
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_flags_are_ignored() {
+        for seed in 0..20 {
+            let mut game = Game::new(
+                9,
+                9,
+                10,
+                seed,
+                Some(SafeStartPolicy::Opening),
+                Generation::Random,
+            );
+            game.reveal(Coordinates { x: 4, y: 4 });
+            if game.is_over() {
+                continue;
+            }
+            // 在与数字相邻的安全tile上插旗，使数字看起来已经满足
+            let wrong: Vec<_> = (0..9)
+                .flat_map(|y| (0..9).map(move |x| Coordinates { x, y }))
+                .filter(|c| game.state_at(*c) == Some(TileState::Covered))
+                .filter(|c| !game.tile_map().is_bombs_at(*c))
+                .filter(|c| {
+                    game.tile_map()
+                        .safe_square_at(*c)
+                        .any(|n| game.state_at(n) == Some(TileState::Uncovered))
+                })
+                .collect();
+            for coords in wrong {
+                game.toggle_flag(coords);
+            }
+            let Some(hint) = hint(&game) else {
+                continue;
+            };
+            assert_eq!(game.state_at(hint.coords), Some(TileState::Covered));
+            if hint.kind == HintKind::Safe {
+                assert!(!game.tile_map().is_bombs_at(hint.coords), "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn wall_pattern_explained_by_two_numbers() {
+        // 靠墙的1-2：第一行全部翻开，第二行为1 2 1 2 1，第三行为* ? * ? *
+        // 2-1可以证明隔一格的tile安全，但需要把其余已翻开的tile视为已知安全
+        let bombs = [0, 2, 4].map(|x| Coordinates { x, y: 2 });
+        let mut game = Game::from_tile_map(TileMap::with_bombs(5, 3, &bombs));
+        game.reveal(Coordinates { x: 0, y: 0 });
+        assert_eq!(game.covered_count(), 5);
+
+        let hint = hint(&game).unwrap();
+        assert_eq!(hint.kind, HintKind::Safe);
+        assert_eq!(hint.coords, Coordinates { x: 1, y: 2 });
+        assert_eq!(
+            hint.reasons,
+            [Coordinates { x: 2, y: 1 }, Coordinates { x: 3, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn single_number_explanation() {
+        let visible = Visible::parse(
+            "
+            F ?
+            1 1
+            ",
+            None,
+        );
+        assert!(proves_safe(
+            &visible,
+            Coordinates { x: 1, y: 0 },
+            &[Coordinates { x: 0, y: 1 }],
+            None
+        ));
+    }
+
+    #[test]
+    fn explanation_uses_all_needed_numbers() {
+        // 1-2-1：证明中间的tile安全需要用到全部三个数字
+        let visible = Visible::parse(
+            "
+            ? ? ?
+            1 2 1
+            . . .
+            ",
+            None,
+        );
+        let (reasons, mine_count) = explain(&visible, Coordinates { x: 1, y: 0 });
+        let expected: Vec<Coordinates> = (0..3).map(|x| Coordinates { x, y: 1 }).collect();
+        assert_eq!(reasons, expected);
+        assert!(!mine_count);
+    }
+
+    #[test]
+    fn explanation_reports_mine_count() {
+        // 1旁边唯一的未知tile是炸弹，只有一个炸弹时其余tile都安全
+        let visible = Visible::parse("1 ? ? ? ?", Some(1));
+        let (reasons, mine_count) = explain(&visible, Coordinates { x: 3, y: 0 });
+        assert_eq!(reasons, [Coordinates { x: 0, y: 0 }]);
+        assert!(mine_count);
+    }
+}
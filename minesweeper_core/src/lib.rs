@@ -0,0 +1,18 @@
+// 扫雷的核心规则，不依赖bevy，可以单独用于机器人、服务端以及测试
+pub mod coordinates;
+pub mod game;
+pub mod generation;
+pub mod hint;
+pub mod probability;
+pub mod solver;
+pub mod tile;
+pub mod tile_map;
+
+pub use coordinates::Coordinates;
+pub use game::{Action, Game, GameStatus, Reveal, SafeStartPolicy, TileState};
+pub use generation::{Generation, NoGuessFallback};
+pub use hint::{Hint, HintKind};
+pub use probability::Probabilities;
+pub use solver::{Cell, Deduction, Visible};
+pub use tile::Tile;
+pub use tile_map::TileMap;
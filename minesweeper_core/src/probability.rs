@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    coordinates::Coordinates,
+    solver::{self, Solutions, Visible},
+};
+
+// 区域过大无法穷举时每个区域的抽样次数
+pub const SAMPLES: usize = 2_000;
+
+// 每个未翻开的tile是炸弹的概率
+#[derive(Debug, Clone, Default)]
+pub struct Probabilities {
+    probabilities: HashMap<Coordinates, f64>,
+    exact: bool, // 为false时部分区域的概率来自抽样，只是近似值
+}
+
+impl Probabilities {
+    pub fn get(&self, coords: Coordinates) -> Option<f64> {
+        self.probabilities.get(&coords).copied()
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, f64)> + '_ {
+        self.probabilities.iter().map(|(c, p)| (*c, *p))
+    }
+
+    // 是炸弹的概率最低的tile，概率相同时取坐标最小的
+    pub fn safest(&self) -> Option<(Coordinates, f64)> {
+        self.iter()
+            .min_by(|(a, p), (b, q)| p.total_cmp(q).then(a.cmp(b)))
+    }
+}
+
+// 计算每个未翻开tile是炸弹的概率，已知炸弹总数时同时考虑剩余的炸弹数
+// 炸弹总数未知时不与任何数字相邻的tile没有概率
+pub fn probabilities(visible: &Visible, seed: u64) -> Probabilities {
+    let (mines, safe) = solver::deduce_sets(visible);
+    let mut probabilities: HashMap<Coordinates, f64> = HashMap::new();
+    probabilities.extend(mines.iter().map(|c| (*c, 1.)));
+    probabilities.extend(safe.iter().map(|c| (*c, 0.)));
+
+    let frontier = solver::frontier(visible, &mines, &safe);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut exact = true;
+    let solutions: Vec<Solutions> = frontier
+        .components
+        .iter()
+        .map(|component| {
+            solver::enumerate(component).unwrap_or_else(|| {
+                exact = false;
+                solver::sample(component, SAMPLES, &mut rng)
+            })
+        })
+        .collect();
+    // 没有满足约束的分布（插错了旗或者抽样失败）时只返回确定的结果
+    if solutions.iter().any(|s| s.by_mines.is_empty()) {
+        return Probabilities {
+            probabilities,
+            exact: false,
+        };
+    }
+    let weights: Vec<Vec<f64>> = solutions.iter().map(weights).collect();
+
+    let Some(remaining) = frontier.remaining_mines else {
+        // 炸弹总数未知时每个区域相互独立
+        for (component, solutions) in frontier.components.iter().zip(solutions.iter()) {
+            let total: u64 = solutions.by_mines.values().map(|(count, _)| count).sum();
+            for (i, coords) in component.cells.iter().enumerate() {
+                let mines: u64 = solutions.by_mines.values().map(|(_, cells)| cells[i]).sum();
+                probabilities.insert(*coords, mines as f64 / total as f64);
+            }
+        }
+        return Probabilities {
+            probabilities,
+            exact,
+        };
+    };
+
+    // 不与数字相邻的tile中放置剩余炸弹的方式数，下标为区域中的炸弹总数
+    let outside = frontier.outside.len();
+    let max_frontier: usize = weights.iter().map(|w| w.len() - 1).sum();
+    let outside_ways = outside_weights(outside, remaining, max_frontier);
+    let all = weights.iter().fold(vec![1.], |acc, w| convolve(&acc, w));
+    let total: f64 = all
+        .iter()
+        .zip(outside_ways.iter())
+        .map(|(w, o)| w * o)
+        .sum();
+    if total == 0. {
+        return Probabilities {
+            probabilities,
+            exact: false,
+        };
+    }
+
+    for (index, component) in frontier.components.iter().enumerate() {
+        // 其余区域的炸弹数分布
+        let others = weights
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != index)
+            .fold(vec![1.], |acc, (_, w)| convolve(&acc, w));
+        let mut cells = vec![0.; component.cells.len()];
+        for (k, (count, per_cell)) in solutions[index].by_mines.iter() {
+            let ways: f64 = others
+                .iter()
+                .enumerate()
+                .map(|(s, w)| w * outside_ways.get(k + s).copied().unwrap_or(0.))
+                .sum();
+            let weight = weights[index][*k] * ways / *count as f64;
+            for (i, mines) in per_cell.iter().enumerate() {
+                cells[i] += weight * *mines as f64;
+            }
+        }
+        for (coords, p) in component.cells.iter().zip(cells) {
+            probabilities.insert(*coords, p / total);
+        }
+    }
+
+    if outside > 0 {
+        // 剩余炸弹平均分布在不与数字相邻的tile中
+        let expected: f64 = all
+            .iter()
+            .zip(outside_ways.iter())
+            .enumerate()
+            .map(|(t, (w, o))| w * o * remaining.saturating_sub(t) as f64)
+            .sum();
+        let p = expected / total / outside as f64;
+        for coords in frontier.outside.iter() {
+            probabilities.insert(*coords, p);
+        }
+    }
+
+    Probabilities {
+        probabilities,
+        exact,
+    }
+}
+
+// 区域中每种炸弹数对应的分布数，除以最大值避免溢出（比例不变）
+fn weights(solutions: &Solutions) -> Vec<f64> {
+    let max_mines = solutions.by_mines.keys().last().copied().unwrap_or(0);
+    let max_count = solutions
+        .by_mines
+        .values()
+        .map(|(count, _)| *count)
+        .max()
+        .unwrap_or(1) as f64;
+    let mut weights = vec![0.; max_mines + 1];
+    for (k, (count, _)) in solutions.by_mines.iter() {
+        weights[*k] = *count as f64 / max_count;
+    }
+    weights
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+// 区域中共有t个炸弹时，在outside个tile中放置剩余炸弹的方式数C(outside, remaining - t)
+// 使用对数计算并除以最大值避免溢出
+fn outside_weights(outside: usize, remaining: usize, max_frontier: usize) -> Vec<f64> {
+    let ln_binomial = |n: usize, r: usize| -> f64 {
+        (0..r)
+            .map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln())
+            .sum()
+    };
+    let logs: Vec<Option<f64>> = (0..=max_frontier)
+        .map(|t| {
+            let r = remaining.checked_sub(t)?;
+            (r <= outside).then(|| ln_binomial(outside, r))
+        })
+        .collect();
+    let max = logs
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    logs.iter()
+        .map(|l| l.map_or(0., |l| (l - max).exp()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solver::Cell, Game, Generation, SafeStartPolicy};
+
+    fn coords(x: u16, y: u16) -> Coordinates {
+        Coordinates { x, y }
+    }
+
+    // 穷举所有炸弹布置计算概率
+    fn brute_force(visible: &Visible) -> HashMap<Coordinates, f64> {
+        let hidden: Vec<Coordinates> = visible
+            .coordinates()
+            .filter(|c| visible.cell(*c) == Some(Cell::Hidden))
+            .collect();
+        let total = visible.total_mines().unwrap() as u32;
+        let mut counts = vec![0u64; hidden.len()];
+        let mut layouts = 0u64;
+        for mask in 0u32..(1 << hidden.len()) {
+            if mask.count_ones() != total {
+                continue;
+            }
+            let is_mine = |c: Coordinates| {
+                hidden
+                    .iter()
+                    .position(|h| *h == c)
+                    .is_some_and(|i| mask & (1 << i) != 0)
+            };
+            let consistent = visible.coordinates().all(|c| match visible.cell(c) {
+                Some(Cell::Revealed(n)) => {
+                    visible.neighbors(c).filter(|n| is_mine(*n)).count() == n as usize
+                }
+                _ => true,
+            });
+            if !consistent {
+                continue;
+            }
+            layouts += 1;
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count += (mask >> i & 1) as u64;
+            }
+        }
+        hidden
+            .into_iter()
+            .zip(counts)
+            .map(|(c, n)| (c, n as f64 / layouts as f64))
+            .collect()
+    }
+
+    #[test]
+    fn fifty_fifty() {
+        let visible = Visible::parse(
+            "
+            ? ?
+            1 1
+            ",
+            None,
+        );
+        let probabilities = probabilities(&visible, 0);
+        assert!(probabilities.is_exact());
+        assert_eq!(probabilities.get(coords(0, 0)), Some(0.5));
+        assert_eq!(probabilities.get(coords(1, 0)), Some(0.5));
+        assert_eq!(probabilities.get(coords(0, 1)), None);
+    }
+
+    #[test]
+    fn remaining_mines_shift_probabilities() {
+        // 左侧的1对应的两个tile中可能有1个炸弹，右侧四个tile不与数字相邻
+        let layout = "
+            ? ? ? ?
+            ? 1 ? ?
+            ";
+        let visible = Visible::parse(layout, Some(2));
+        let result = probabilities(&visible, 0);
+        let expected = brute_force(&visible);
+        for (coords, p) in expected {
+            assert!((result.get(coords).unwrap() - p).abs() < 1e-9, "{}", coords);
+        }
+    }
+
+    #[test]
+    fn matches_brute_force_on_small_games() {
+        for seed in 0..30 {
+            let mut game = Game::new(
+                5,
+                4,
+                4,
+                seed,
+                Some(SafeStartPolicy::SafeCell),
+                Generation::Random,
+            );
+            game.reveal(coords(0, 0));
+            if game.is_over() {
+                continue;
+            }
+            let visible = Visible::from_game(&game);
+            let result = game.mine_probabilities();
+            for (coords, p) in brute_force(&visible) {
+                let actual = result.get(coords).unwrap();
+                assert!((actual - p).abs() < 1e-9, "seed {} {}", seed, coords);
+            }
+        }
+    }
+
+    #[test]
+    fn safest_prefers_lowest_probability() {
+        let visible = Visible::parse(
+            "
+            ? ? ?
+            1 1 ?
+            ",
+            Some(1),
+        );
+        let (coords, p) = probabilities(&visible, 0).safest().unwrap();
+        assert_eq!(p, 0.);
+        assert_eq!(coords, self::coords(2, 0));
+    }
+}
@@ -0,0 +1,803 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    coordinates::Coordinates,
+    game::{Game, TileState},
+    tile::Tile,
+    tile_map::SQUARE_COORDINATES,
+};
+
+// 玩家能看到的某个tile的状态
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cell {
+    Hidden,       // 未翻开
+    Flagged,      // 未翻开且被插旗，求解时视为炸弹
+    Revealed(u8), // 已翻开，值为周围的炸弹数
+    Safe,         // 已知不是炸弹但不提供数字，解释提示时用于忽略无关的数字
+}
+
+// 玩家能看到的board，求解时只能使用这些信息
+#[derive(Debug, Clone)]
+pub struct Visible {
+    width: u16,
+    height: u16,
+    cells: Vec<Vec<Cell>>,
+    total_mines: Option<u16>, // 炸弹总数，炸弹还未布置时为None
+}
+
+// 逻辑推导的结果，只包含仍未翻开的tile
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Deduction {
+    pub safe: BTreeSet<Coordinates>,  // 一定不是炸弹的tile
+    pub mines: BTreeSet<Coordinates>, // 一定是炸弹但还没有插旗的tile
+}
+
+impl Deduction {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+// 某个数字块周围未知的tile中还有needed个炸弹
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Constraint {
+    cells: BTreeSet<Coordinates>,
+    needed: usize,
+}
+
+impl Visible {
+    pub fn from_game(game: &Game) -> Self {
+        let cells = (0..game.height())
+            .map(|y| {
+                (0..game.width())
+                    .map(|x| {
+                        let coords = Coordinates { x, y };
+                        match game.state_at(coords) {
+                            Some(TileState::Uncovered) => match game.tile_map().tile_at(coords) {
+                                Some(Tile::BombNeighbor(count)) => Cell::Revealed(count),
+                                _ => Cell::Revealed(0),
+                            },
+                            Some(TileState::Flagged) => Cell::Flagged,
+                            _ => Cell::Hidden,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            width: game.width(),
+            height: game.height(),
+            cells,
+            total_mines: game.mines_placed().then_some(game.bomb_count()),
+        }
+    }
+
+    // 把插旗的tile视为未翻开，只根据已翻开的数字推导
+    pub fn without_flags(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|cell| match cell {
+                        Cell::Flagged => Cell::Hidden,
+                        cell => *cell,
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { cells, ..*self }
+    }
+
+    // 由每一行的Cell构建，所有行的长度需要相同
+    pub fn new(cells: Vec<Vec<Cell>>, total_mines: Option<u16>) -> Self {
+        let height = cells.len() as u16;
+        let width = cells.first().map_or(0, |line| line.len()) as u16;
+        assert!(
+            cells.iter().all(|line| line.len() == width as usize),
+            "all lines must have the same width"
+        );
+        Self {
+            width,
+            height,
+            cells,
+            total_mines,
+        }
+    }
+
+    // 从文本解析，每行对应一行tile（第一行y为0）
+    // '?'为未翻开，'F'为插旗，'.'或'0'为空白，'1'-'8'为数字，空白字符被忽略
+    pub fn parse(layout: &str, total_mines: Option<u16>) -> Self {
+        let cells = layout
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| match c {
+                        '?' => Cell::Hidden,
+                        'F' => Cell::Flagged,
+                        '.' => Cell::Revealed(0),
+                        '0'..='8' => Cell::Revealed(c as u8 - b'0'),
+                        _ => panic!("unexpected character {:?} in layout", c),
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::new(cells, total_mines)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn total_mines(&self) -> Option<u16> {
+        self.total_mines
+    }
+
+    pub fn cell(&self, coords: Coordinates) -> Option<Cell> {
+        if coords.x >= self.width || coords.y >= self.height {
+            return None;
+        }
+        Some(self.cells[coords.y as usize][coords.x as usize])
+    }
+
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+    }
+
+    // 某个坐标在地图范围内的相邻坐标
+    pub fn neighbors(&self, coords: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        SQUARE_COORDINATES
+            .iter()
+            .map(move |delta| coords + *delta)
+            .filter(|c| c.x < self.width && c.y < self.height)
+    }
+}
+
+// 穷举时单个相连区域最多包含的未知tile数，超过时只使用规则推导
+pub const MAX_ENUMERATION_CELLS: usize = 24;
+
+// 根据已翻开的数字推导出一定安全以及一定是炸弹的tile
+// 先使用单点规则、相邻数字之间的子集/重叠规则，规则无法继续推导时
+// 对较小的边界区域穷举所有满足约束的炸弹分布（已知炸弹总数时同时考虑总数）
+pub fn deduce(visible: &Visible) -> Deduction {
+    let (mines, safe) = deduce_sets(visible);
+    Deduction {
+        safe: safe.into_iter().collect(),
+        mines: mines
+            .into_iter()
+            .filter(|c| visible.cell(*c) != Some(Cell::Flagged))
+            .collect(),
+    }
+}
+
+// 推导出的炸弹（包括插旗的tile）以及安全的tile
+pub(crate) fn deduce_sets(visible: &Visible) -> (HashSet<Coordinates>, HashSet<Coordinates>) {
+    let mut mines: HashSet<Coordinates> = visible
+        .coordinates()
+        .filter(|c| visible.cell(*c) == Some(Cell::Flagged))
+        .collect();
+    let mut safe: HashSet<Coordinates> = HashSet::new();
+
+    loop {
+        apply_rules(visible, &mut mines, &mut safe);
+        if !apply_enumeration(visible, &mut mines, &mut safe) {
+            break;
+        }
+    }
+    (mines, safe)
+}
+
+// 只使用规则推导，不进行穷举
+pub fn deduce_with_rules(visible: &Visible) -> Deduction {
+    let flags: HashSet<Coordinates> = visible
+        .coordinates()
+        .filter(|c| visible.cell(*c) == Some(Cell::Flagged))
+        .collect();
+    let mut mines = flags.clone();
+    let mut safe: HashSet<Coordinates> = HashSet::new();
+    apply_rules(visible, &mut mines, &mut safe);
+
+    Deduction {
+        safe: safe.into_iter().collect(),
+        mines: mines.difference(&flags).copied().collect(),
+    }
+}
+
+// 反复应用规则直到无法继续推导
+fn apply_rules(
+    visible: &Visible,
+    mines: &mut HashSet<Coordinates>,
+    safe: &mut HashSet<Coordinates>,
+) {
+    loop {
+        let constraints = constraints(visible, mines, safe);
+        let mut changed = false;
+        // 单点规则
+        for constraint in constraints.iter() {
+            changed |= apply(&constraint.cells, constraint.needed, mines, safe);
+        }
+        if changed {
+            continue;
+        }
+        for a in constraints.iter() {
+            for b in constraints.iter() {
+                if a == b || a.cells.is_disjoint(&b.cells) {
+                    continue;
+                }
+                let b_only: BTreeSet<Coordinates> = b.cells.difference(&a.cells).copied().collect();
+                if a.cells.is_subset(&b.cells) {
+                    // 子集规则：B\A中的炸弹数为B与A的差
+                    if b.needed >= a.needed {
+                        changed |= apply(&b_only, b.needed - a.needed, mines, safe);
+                    }
+                } else if b.needed >= a.needed && b.needed - a.needed == b_only.len() {
+                    // 重叠规则：A∩B最多有A个炸弹，B\A至少有B-A个炸弹
+                    // 恰好等于B\A的数量时B\A全是炸弹，A的炸弹全部在A∩B中，A\B全部安全
+                    let a_only: BTreeSet<Coordinates> =
+                        a.cells.difference(&b.cells).copied().collect();
+                    changed |= apply(&b_only, b_only.len(), mines, safe);
+                    changed |= apply(&a_only, 0, mines, safe);
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+// cells中有needed个炸弹：needed为0时全部安全，等于cells数量时全部是炸弹
+fn apply(
+    cells: &BTreeSet<Coordinates>,
+    needed: usize,
+    mines: &mut HashSet<Coordinates>,
+    safe: &mut HashSet<Coordinates>,
+) -> bool {
+    if cells.is_empty() {
+        return false;
+    }
+    let target = if needed == 0 {
+        safe
+    } else if needed == cells.len() {
+        mines
+    } else {
+        return false;
+    };
+    let mut changed = false;
+    for c in cells {
+        changed |= target.insert(*c);
+    }
+    changed
+}
+
+// 每个与未知tile相邻的数字块构成一个约束
+fn constraints(
+    visible: &Visible,
+    mines: &HashSet<Coordinates>,
+    safe: &HashSet<Coordinates>,
+) -> Vec<Constraint> {
+    let mut constraints = Vec::new();
+    for coords in visible.coordinates() {
+        let Some(Cell::Revealed(count)) = visible.cell(coords) else {
+            continue;
+        };
+        let mut cells = BTreeSet::new();
+        let mut known_mines = 0;
+        for neighbor in visible.neighbors(coords) {
+            if mines.contains(&neighbor) {
+                known_mines += 1;
+            } else if visible.cell(neighbor) == Some(Cell::Hidden) && !safe.contains(&neighbor) {
+                cells.insert(neighbor);
+            }
+        }
+        if cells.is_empty() {
+            continue;
+        }
+        let constraint = Constraint {
+            cells,
+            needed: (count as usize).saturating_sub(known_mines),
+        };
+        if !constraints.contains(&constraint) {
+            constraints.push(constraint);
+        }
+    }
+    constraints
+}
+
+// 边界上一个相连的区域：区域内的tile只受区域内的约束影响
+#[derive(Debug, Clone)]
+pub(crate) struct Component {
+    pub cells: Vec<Coordinates>,
+    constraints: Vec<(Vec<usize>, usize)>, // 约束涉及的tile在cells中的下标以及需要的炸弹数
+}
+
+// 一个区域所有满足约束的炸弹分布，按照炸弹数分组
+// 值为该炸弹数下的分布数目以及每个tile在这些分布中是炸弹的次数
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Solutions {
+    pub by_mines: BTreeMap<usize, (u64, Vec<u64>)>,
+}
+
+// 边界区域以及不与任何数字相邻的未知tile
+pub(crate) struct Frontier {
+    pub components: Vec<Component>,
+    pub outside: Vec<Coordinates>,
+    pub remaining_mines: Option<usize>, // 未知tile中剩余的炸弹数，未知炸弹总数时为None
+}
+
+pub(crate) fn frontier(
+    visible: &Visible,
+    mines: &HashSet<Coordinates>,
+    safe: &HashSet<Coordinates>,
+) -> Frontier {
+    let constraints = constraints(visible, mines, safe);
+
+    // 用并查集将共享约束的tile合并为同一区域
+    let cells: Vec<Coordinates> = constraints
+        .iter()
+        .flat_map(|c| c.cells.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let index: HashMap<Coordinates, usize> =
+        cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let mut parent: Vec<usize> = (0..cells.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    for constraint in constraints.iter() {
+        let mut iter = constraint.cells.iter().map(|c| index[c]);
+        let first = iter.next().unwrap();
+        for other in iter {
+            let (a, b) = (find(&mut parent, first), find(&mut parent, other));
+            parent[a] = b;
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<Coordinates>> = BTreeMap::new();
+    for (i, coords) in cells.iter().enumerate() {
+        groups
+            .entry(find(&mut parent, i))
+            .or_default()
+            .push(*coords);
+    }
+    let components = groups
+        .into_values()
+        .map(|cells| {
+            let local: HashMap<Coordinates, usize> =
+                cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+            let constraints = constraints
+                .iter()
+                .filter(|c| local.contains_key(c.cells.iter().next().unwrap()))
+                .map(|c| (c.cells.iter().map(|c| local[c]).collect(), c.needed))
+                .collect();
+            Component { cells, constraints }
+        })
+        .collect();
+
+    let outside: Vec<Coordinates> = visible
+        .coordinates()
+        .filter(|c| {
+            visible.cell(*c) == Some(Cell::Hidden)
+                && !mines.contains(c)
+                && !safe.contains(c)
+                && !index.contains_key(c)
+        })
+        .collect();
+    let remaining_mines = visible
+        .total_mines
+        .map(|total| (total as usize).saturating_sub(mines.len()));
+
+    Frontier {
+        components,
+        outside,
+        remaining_mines,
+    }
+}
+
+// 回溯穷举区域内所有满足约束的炸弹分布，区域过大时返回None
+pub(crate) fn enumerate(component: &Component) -> Option<Solutions> {
+    if component.cells.len() > MAX_ENUMERATION_CELLS {
+        return None;
+    }
+    let mut search = Search::new(component, None);
+    search.run(0);
+    Some(search.solutions)
+}
+
+// 单次抽样最多访问的节点数，超过时放弃这次抽样
+const SAMPLE_BUDGET: usize = 10_000;
+
+// 区域过大无法穷举时，随机搜索得到若干个满足约束的分布作为近似
+pub(crate) fn sample(component: &Component, samples: usize, rng: &mut ChaCha8Rng) -> Solutions {
+    let mut search = Search::new(component, Some(rng));
+    for _ in 0..samples {
+        search.budget = SAMPLE_BUDGET;
+        search.run(0);
+    }
+    search.solutions
+}
+
+struct Search<'a> {
+    component: &'a Component,
+    cell_constraints: Vec<Vec<usize>>,
+    placed: Vec<usize>,     // 每个约束已经放置的炸弹数
+    unassigned: Vec<usize>, // 每个约束还未确定的tile数
+    assignment: Vec<bool>,
+    solutions: Solutions,
+    rng: Option<&'a mut ChaCha8Rng>, // 抽样时随机决定分支的顺序，找到一个分布后停止
+    budget: usize,                   // 剩余可以访问的节点数
+}
+
+impl<'a> Search<'a> {
+    fn new(component: &'a Component, rng: Option<&'a mut ChaCha8Rng>) -> Self {
+        let mut cell_constraints = vec![Vec::new(); component.cells.len()];
+        for (i, (cells, _)) in component.constraints.iter().enumerate() {
+            for cell in cells {
+                cell_constraints[*cell].push(i);
+            }
+        }
+        Self {
+            component,
+            cell_constraints,
+            placed: vec![0; component.constraints.len()],
+            unassigned: component.constraints.iter().map(|(c, _)| c.len()).collect(),
+            assignment: vec![false; component.cells.len()],
+            solutions: Solutions::default(),
+            rng,
+            budget: usize::MAX,
+        }
+    }
+
+    // 返回true时停止搜索
+    fn run(&mut self, cell: usize) -> bool {
+        if self.budget == 0 {
+            return true;
+        }
+        self.budget -= 1;
+        if cell == self.assignment.len() {
+            let mines = self.assignment.iter().filter(|m| **m).count();
+            let (count, per_cell) = self
+                .solutions
+                .by_mines
+                .entry(mines)
+                .or_insert_with(|| (0, vec![0; self.assignment.len()]));
+            *count += 1;
+            for (i, mine) in self.assignment.iter().enumerate() {
+                per_cell[i] += *mine as u64;
+            }
+            return self.rng.is_some();
+        }
+        let mine_first = self.rng.as_mut().is_some_and(|rng| rng.gen_bool(0.5));
+        let branches = if mine_first {
+            [true, false]
+        } else {
+            [false, true]
+        };
+        for mine in branches {
+            let feasible = self.cell_constraints[cell].iter().all(|c| {
+                let placed = self.placed[*c] + mine as usize;
+                let needed = self.component.constraints[*c].1;
+                placed <= needed && placed + self.unassigned[*c] > needed
+            });
+            if !feasible {
+                continue;
+            }
+            for c in self.cell_constraints[cell].iter() {
+                self.placed[*c] += mine as usize;
+                self.unassigned[*c] -= 1;
+            }
+            self.assignment[cell] = mine;
+            let stop = self.run(cell + 1);
+            for c in self.cell_constraints[cell].iter() {
+                self.placed[*c] -= mine as usize;
+                self.unassigned[*c] += 1;
+            }
+            if stop {
+                self.assignment[cell] = false;
+                return true;
+            }
+        }
+        self.assignment[cell] = false;
+        false
+    }
+}
+
+// 每个区域在考虑炸弹总数后仍然可能的炸弹数
+// 无法确定总数或有区域未被穷举时不做限制
+pub(crate) fn feasible_mine_counts(
+    frontier: &Frontier,
+    solutions: &[Solutions],
+) -> Vec<BTreeSet<usize>> {
+    let all: Vec<BTreeSet<usize>> = solutions
+        .iter()
+        .map(|s| s.by_mines.keys().copied().collect())
+        .collect();
+    let Some(remaining) = frontier.remaining_mines else {
+        return all;
+    };
+    let min_total = remaining.saturating_sub(frontier.outside.len());
+    (0..all.len())
+        .map(|i| {
+            // 其余区域所有可能的炸弹总数
+            let mut sums = BTreeSet::from([0]);
+            for (j, counts) in all.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                sums = sums
+                    .iter()
+                    .flat_map(|s| counts.iter().map(move |k| s + k))
+                    .filter(|s| *s <= remaining)
+                    .collect();
+            }
+            all[i]
+                .iter()
+                .copied()
+                .filter(|k| {
+                    sums.iter()
+                        .any(|s| s + k <= remaining && s + k >= min_total)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 穷举边界区域进行推导，返回是否推导出新的结果
+fn apply_enumeration(
+    visible: &Visible,
+    mines: &mut HashSet<Coordinates>,
+    safe: &mut HashSet<Coordinates>,
+) -> bool {
+    let frontier = frontier(visible, mines, safe);
+    let solutions: Option<Vec<Solutions>> = frontier.components.iter().map(enumerate).collect();
+    let mut changed = false;
+
+    let Some(solutions) = solutions else {
+        // 有区域过大时，只对能穷举的区域单独推导，其中出现矛盾时同样不做任何推导
+        let enumerated: Vec<(&Component, Solutions)> = frontier
+            .components
+            .iter()
+            .filter_map(|component| Some((component, enumerate(component)?)))
+            .collect();
+        if enumerated.iter().any(|(_, s)| s.by_mines.is_empty()) {
+            return false;
+        }
+        for (component, solutions) in enumerated.iter() {
+            let feasible = solutions.by_mines.keys().copied().collect();
+            changed |= apply_solutions(component, solutions, &feasible, mines, safe);
+        }
+        return changed;
+    };
+
+    let feasible = feasible_mine_counts(&frontier, &solutions);
+    // 出现矛盾（例如插错了旗）时不做任何推导
+    if feasible.iter().any(|f| f.is_empty()) {
+        return false;
+    }
+    for (i, component) in frontier.components.iter().enumerate() {
+        changed |= apply_solutions(component, &solutions[i], &feasible[i], mines, safe);
+    }
+
+    // 不与数字相邻的tile：所有可能的分布中剩余的炸弹数都为0或者都等于其数量
+    if let (Some(remaining), false) = (frontier.remaining_mines, frontier.outside.is_empty()) {
+        let (min, max) = feasible.iter().fold((0, 0), |(min, max), f| {
+            (min + f.first().unwrap(), max + f.last().unwrap())
+        });
+        let outside: BTreeSet<Coordinates> = frontier.outside.iter().copied().collect();
+        if min >= remaining {
+            changed |= apply(&outside, 0, mines, safe);
+        } else if remaining - min == outside.len() && remaining - max == outside.len() {
+            changed |= apply(&outside, outside.len(), mines, safe);
+        }
+    }
+    changed
+}
+
+fn apply_solutions(
+    component: &Component,
+    solutions: &Solutions,
+    feasible: &BTreeSet<usize>,
+    mines: &mut HashSet<Coordinates>,
+    safe: &mut HashSet<Coordinates>,
+) -> bool {
+    let mut changed = false;
+    for (i, coords) in component.cells.iter().enumerate() {
+        let always = feasible.iter().all(|k| {
+            let (count, per_cell) = &solutions.by_mines[k];
+            per_cell[i] == *count
+        });
+        let never = feasible.iter().all(|k| solutions.by_mines[k].1[i] == 0);
+        if never {
+            changed |= safe.insert(*coords);
+        } else if always {
+            changed |= mines.insert(*coords);
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords(x: u16, y: u16) -> Coordinates {
+        Coordinates { x, y }
+    }
+
+    fn set(items: &[(u16, u16)]) -> BTreeSet<Coordinates> {
+        items.iter().map(|(x, y)| coords(*x, *y)).collect()
+    }
+
+    #[test]
+    fn single_point_rules() {
+        // 角上的1只有一个未知邻居，一定是炸弹；另一侧的1已经满足，其余邻居安全
+        let visible = Visible::parse(
+            "
+            1 ? ?
+            1 1 ?
+            . 1 ?
+            ",
+            None,
+        );
+        let deduction = deduce_with_rules(&visible);
+        assert_eq!(deduction.mines, set(&[(1, 0)]));
+        assert_eq!(deduction.safe, set(&[(2, 0), (2, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn flags_count_as_mines() {
+        let visible = Visible::parse(
+            "
+            F ?
+            1 ?
+            ",
+            None,
+        );
+        let deduction = deduce(&visible);
+        assert!(deduction.mines.is_empty());
+        assert_eq!(deduction.safe, set(&[(1, 0), (1, 1)]));
+    }
+
+    #[test]
+    fn subset_rule() {
+        // 1-2-1：两端的1各自对应一个炸弹，中间的tile安全
+        let visible = Visible::parse(
+            "
+            ? ? ?
+            1 2 1
+            . . .
+            ",
+            None,
+        );
+        let deduction = deduce_with_rules(&visible);
+        assert_eq!(deduction.mines, set(&[(0, 0), (2, 0)]));
+        assert_eq!(deduction.safe, set(&[(1, 0)]));
+    }
+
+    #[test]
+    fn overlap_rule() {
+        // 中间的1周围(0,0),(1,0),(2,0)最多一个炸弹，2还缺的一个炸弹只能是(3,0)，
+        // 于是1的炸弹在(1,0),(2,0)之中，(0,0)安全
+        let visible = Visible::parse(
+            "
+            ? ? ? ?
+            1 1 2 1
+            ",
+            None,
+        );
+        let deduction = deduce_with_rules(&visible);
+        assert_eq!(deduction.mines, set(&[(1, 0), (3, 0)]));
+        assert_eq!(deduction.safe, set(&[(0, 0), (2, 0)]));
+    }
+
+    #[test]
+    fn enumeration_fallback() {
+        // 规则无法推导，穷举所有满足约束的分布后才能确定
+        let visible = Visible::parse(
+            "
+            ? ? ? ? ? ?
+            ? 3 1 2 F F
+            ? 1 . 2 3 3
+            ? 2 1 1 F 1
+            ? F 1 1 1 1
+            ",
+            None,
+        );
+        assert!(deduce_with_rules(&visible).is_empty());
+        let deduction = deduce(&visible);
+        assert_eq!(deduction.mines, set(&[(0, 0)]));
+        assert_eq!(deduction.safe, set(&[(3, 0), (0, 3)]));
+    }
+
+    #[test]
+    fn global_mine_count() {
+        // 左侧两个1的炸弹在(0,0),(1,0)之中，右侧两个tile不与任何数字相邻
+        let layout = "
+            ? ? ? ?
+            1 1 ? ?
+            ";
+        let unknown = deduce(&Visible::parse(layout, None));
+        assert_eq!(unknown.safe, set(&[(2, 0), (2, 1)]));
+        // 只有一个炸弹时右侧都安全
+        let one = deduce(&Visible::parse(layout, Some(1)));
+        assert_eq!(one.safe, set(&[(2, 0), (2, 1), (3, 0), (3, 1)]));
+        // 三个炸弹时右侧都是炸弹
+        let three = deduce(&Visible::parse(layout, Some(3)));
+        assert_eq!(three.mines, set(&[(3, 0), (3, 1)]));
+    }
+
+    #[test]
+    fn deductions_match_actual_mines() {
+        for seed in 0..50 {
+            let mut game = Game::new(
+                9,
+                9,
+                10,
+                seed,
+                Some(crate::SafeStartPolicy::Opening),
+                crate::Generation::Random,
+            );
+            game.reveal(Coordinates { x: 4, y: 4 });
+            let deduction = game.deduce();
+            assert!(deduction
+                .mines
+                .iter()
+                .all(|c| game.tile_map().is_bombs_at(*c)));
+            assert!(deduction
+                .safe
+                .iter()
+                .all(|c| !game.tile_map().is_bombs_at(*c)));
+        }
+    }
+
+    #[test]
+    fn contradiction_deduces_nothing() {
+        let visible = Visible::parse(
+            "
+            F F ?
+            1 . .
+            ",
+            None,
+        );
+        let deduction = deduce(&visible);
+        assert!(deduction.mines.is_empty());
+    }
+
+    #[test]
+    fn contradiction_with_large_component_deduces_nothing() {
+        // 第一行的区域过大无法穷举；左下角的1和3只有两个未知邻居，互相矛盾
+        let hidden = vec!["?"; MAX_ENUMERATION_CELLS + 6].join(" ");
+        let ones = vec!["1"; MAX_ENUMERATION_CELLS + 6].join(" ");
+        let zeros = vec!["."; MAX_ENUMERATION_CELLS + 6].join(" ");
+        let rest = vec!["."; MAX_ENUMERATION_CELLS + 4].join(" ");
+        let layout = format!(
+            "{}\n{}\n{}\n? ? {}\n1 3 {}",
+            hidden, ones, zeros, rest, rest
+        );
+        let visible = Visible::parse(&layout, None);
+        let frontier = frontier(&visible, &HashSet::new(), &HashSet::new());
+        assert!(frontier.components.iter().any(|c| enumerate(c).is_none()));
+
+        let (mut mines, mut safe) = (HashSet::new(), HashSet::new());
+        assert!(!apply_enumeration(&visible, &mut mines, &mut safe));
+        assert!(mines.is_empty());
+        assert!(safe.is_empty());
+    }
+}
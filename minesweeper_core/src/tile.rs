@@ -1,8 +1,9 @@
 #[cfg(feature = "debug")]
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
 // 定义描述扫雷中每一个方块的一个枚举类型
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Tile {
     Bomb,             // 炸弹
     BombNeighbor(u8), // 炸弹周围有数字的块
@@ -0,0 +1,229 @@
+use crate::{coordinates::Coordinates, tile::Tile};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+pub(crate) const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    bomb_count: u16,
+    seed: u64,
+    height: u16,
+    width: u16,
+    map: Vec<Vec<Tile>>,
+}
+
+impl TileMap {
+    pub fn new(width: u16, height: u16) -> Self {
+        let map = (0..height)
+            .map(|_| (0..width).map(|_| Tile::Empty).collect())
+            .collect();
+
+        Self {
+            bomb_count: 0,
+            seed: 0,
+            height,
+            width,
+            map,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    #[allow(unused)]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    #[allow(unused)]
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    // 布置炸弹时使用的随机种子
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // 坐标是否在地图范围内
+    pub fn contains(&self, position: Coordinates) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    // 获取某个坐标周围的8个位置的坐标
+    pub fn safe_square_at(&self, position: Coordinates) -> impl Iterator<Item = Coordinates> {
+        SQUARE_COORDINATES
+            .iter()
+            .copied()
+            .map(move |coord| position + coord)
+    }
+
+    pub fn is_bombs_at(&self, position: Coordinates) -> bool {
+        if position.x >= self.width || position.y >= self.height {
+            return false;
+        }
+        self.map[position.y as usize][position.x as usize].is_bomb()
+    }
+
+    // 获取某个坐标上的tile，超出地图范围时返回None
+    pub fn tile_at(&self, position: Coordinates) -> Option<Tile> {
+        if !self.contains(position) {
+            return None;
+        }
+        Some(self.map[position.y as usize][position.x as usize])
+    }
+
+    pub fn bomb_count_at(&self, position: Coordinates) -> u8 {
+        if self.is_bombs_at(position) {
+            return 0;
+        }
+        self.safe_square_at(position)
+            .filter(|coord| self.is_bombs_at(*coord))
+            .count() as u8
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs (seed: {}):\n",
+            self.width, self.height, self.bomb_count, self.seed
+        );
+        let line = (0..=(self.width + 1)).map(|_| '-').collect::<String>();
+        buffer = format!("{}{}\n", buffer, line);
+
+        for line in self.iter() {
+            buffer = format!("{}|", buffer);
+            for tile in line.iter() {
+                buffer = format!("{}{}", buffer, tile.console_output());
+            }
+            buffer = format!("{}|\n", buffer);
+        }
+        format!("{}{}", buffer, line)
+    }
+}
+
+// 实现炸弹的布置以及数字提示的生成
+impl TileMap {
+    // 相同的种子总是生成相同的布局
+    pub fn set_bombs(&mut self, bomb_num: u16, seed: u64) {
+        self.set_bombs_avoiding(bomb_num, &[], seed);
+    }
+
+    // 布置炸弹，但不会在safe_zone中的位置放置炸弹
+    // 可放置的位置不足时只会布置能放下的炸弹数目
+    pub fn set_bombs_avoiding(&mut self, bomb_num: u16, safe_zone: &[Coordinates], seed: u64) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        self.seed = seed;
+        let candidates: Vec<Coordinates> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinates { x, y }))
+            .filter(|coord| !safe_zone.contains(coord))
+            .collect();
+
+        // 放置炸弹
+        self.bomb_count = 0;
+        for coord in candidates.choose_multiple(&mut rng, bomb_num as usize) {
+            self.map[coord.y as usize][coord.x as usize] = Tile::Bomb;
+            self.bomb_count += 1;
+        }
+        self.set_numbers();
+    }
+
+    // 在指定的位置布置炸弹，用于测试特定的布局
+    #[cfg(test)]
+    pub(crate) fn with_bombs(width: u16, height: u16, bombs: &[Coordinates]) -> Self {
+        let mut tile_map = Self::new(width, height);
+        for coord in bombs {
+            tile_map.map[coord.y as usize][coord.x as usize] = Tile::Bomb;
+        }
+        tile_map.bomb_count = bombs.len() as u16;
+        tile_map.set_numbers();
+        tile_map
+    }
+
+    // 设置炸弹周围的数字
+    fn set_numbers(&mut self) {
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                let position = Coordinates {
+                    x: x as u16,
+                    y: y as u16,
+                };
+                if self.is_bombs_at(position) {
+                    continue;
+                }
+                let neighbor_value = self.bomb_count_at(position);
+                if neighbor_value == 0 {
+                    continue;
+                } else {
+                    self.map[y][x] = Tile::BombNeighbor(neighbor_value);
+                }
+            }
+        }
+    }
+}
+
+impl Deref for TileMap {
+    type Target = Vec<Vec<Tile>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for TileMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_bombs_avoiding_keeps_safe_zone_clear() {
+        let mut tile_map = TileMap::new(5, 5);
+        let center = Coordinates { x: 2, y: 2 };
+        let mut safe_zone: Vec<Coordinates> = tile_map.safe_square_at(center).collect();
+        safe_zone.push(center);
+        tile_map.set_bombs_avoiding(16, &safe_zone, 42);
+
+        assert_eq!(tile_map.bomb_count(), 16);
+        assert!(safe_zone.iter().all(|coord| !tile_map.is_bombs_at(*coord)));
+        assert_eq!(tile_map[2][2], Tile::Empty);
+    }
+
+    #[test]
+    fn set_bombs_avoiding_caps_bomb_count() {
+        let mut tile_map = TileMap::new(3, 3);
+        tile_map.set_bombs_avoiding(20, &[Coordinates { x: 0, y: 0 }], 42);
+
+        assert_eq!(tile_map.bomb_count(), 8);
+        assert!(!tile_map.is_bombs_at(Coordinates { x: 0, y: 0 }));
+    }
+
+    #[test]
+    fn same_seed_same_layout() {
+        let mut a = TileMap::new(16, 16);
+        let mut b = TileMap::new(16, 16);
+        a.set_bombs(40, 7);
+        b.set_bombs(40, 7);
+
+        assert_eq!(*a, *b);
+        assert_eq!(a.seed(), 7);
+    }
+}
@@ -0,0 +1,363 @@
+use std::{fmt, fs, io, path::PathBuf};
+
+use board_plugin::resources::{
+    BoardOptions, BoardOptionsError, BoardPosition, Difficulty, Generation, PanButton,
+    SafeStartPolicy, TileSize,
+};
+use serde::{Deserialize, Serialize};
+
+// 未指定--config时尝试读取的配置文件
+pub const DEFAULT_CONFIG: &str = "minesweeper.ron";
+
+pub const USAGE: &str = "\
+Usage: minesweeper [OPTIONS]
+
+Options:
+  --config <PATH>    Read settings from a RON file (default: minesweeper.ron if it exists)
+  --preset <NAME>    beginner, intermediate, expert or a mine density such as 20%
+  --width <N>        Board width in tiles
+  --height <N>       Board height in tiles
+  --mines <N>        Number of mines
+  --seed <N>         Seed used to place the mines
+  --replay <PATH>    Play back a saved replay
+  --help             Print this message";
+
+// 窗口大小
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowConfig {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 700.,
+            height: 800.,
+        }
+    }
+}
+
+// 配置文件的内容，缺少的字段使用默认值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub window: WindowConfig,
+    #[serde(with = "BoardConfig")]
+    pub board: BoardOptions,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window: WindowConfig::default(),
+            board: default_board(),
+        }
+    }
+}
+
+fn default_board() -> BoardOptions {
+    BoardOptions {
+        map_size: (20, 20),
+        bomb_count: 40,
+        tile_padding: 3.,
+        safe_start: true,
+        hud: true,
+        autosave: true,
+        ..Default::default()
+    }
+}
+
+// 配置文件中的board部分，缺少的字段使用default_board()而不是BoardOptions::default()中的值
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "BoardOptions", default = "default_board")]
+struct BoardConfig {
+    map_size: (u16, u16),
+    bomb_count: u16,
+    position: BoardPosition,
+    tile_size: TileSize,
+    tile_padding: f32,
+    safe_start: bool,
+    safe_start_policy: SafeStartPolicy,
+    seed: Option<u64>,
+    generation: Generation,
+    hud: bool,
+    autosave: bool,
+    pan_zoom: bool,
+    pan_button: PanButton,
+}
+
+// 解析配置文件以及命令行得到的设置
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub config: Config,
+    pub replay: Option<PathBuf>, // 需要回放的录像
+    pub help: bool,              // 只打印用法
+    pub menu: bool,              // 命令行中没有指定难度时先显示难度选择菜单
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        error: ron::error::SpannedError,
+    },
+    UnknownArgument(String),
+    MissingValue(String),
+    InvalidValue {
+        flag: String,
+        value: String,
+    },
+    UnknownPreset(String),
+    Board(BoardOptionsError),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "cannot read {}: {}", path.display(), error),
+            Self::Parse { path, error } => {
+                write!(f, "invalid config {}: {}", path.display(), error)
+            }
+            Self::UnknownArgument(arg) => write!(f, "unknown argument '{}'", arg),
+            Self::MissingValue(flag) => write!(f, "missing value for '{}'", flag),
+            Self::InvalidValue { flag, value } => {
+                write!(f, "invalid value '{}' for '{}'", value, flag)
+            }
+            Self::UnknownPreset(name) => write!(
+                f,
+                "unknown preset '{}' (expected beginner, intermediate, expert or a density such as 20%)",
+                name
+            ),
+            Self::Board(error) => write!(f, "{}", error),
+            Self::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            config: Config::default(),
+            replay: None,
+            help: false,
+            menu: true,
+        }
+    }
+}
+
+// 依次应用默认值、配置文件、预设以及命令行中的单项设置
+pub fn load(args: impl IntoIterator<Item = String>) -> Result<Settings, ConfigError> {
+    let args: Vec<String> = args.into_iter().collect();
+    let mut settings = Settings::default();
+
+    // 先找到配置文件，命令行中的其他参数覆盖配置文件中的值
+    let config_path = value_of(&args, "--config")?.map(PathBuf::from);
+    let default_path = PathBuf::from(DEFAULT_CONFIG);
+    match config_path {
+        Some(path) => settings.config = read_config(path)?,
+        None if default_path.exists() => settings.config = read_config(default_path)?,
+        None => (),
+    }
+
+    let board = &mut settings.config.board;
+    let mut difficulty = None;
+    let (mut width, mut height, mut mines) = (None, None, None);
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| ConfigError::MissingValue(flag.clone()))
+        };
+        match flag.as_str() {
+            "--help" | "-h" => settings.help = true,
+            "--config" => {
+                value()?;
+            }
+            "--preset" => difficulty = Some(preset(&value()?)?),
+            "--width" => width = Some(parse(flag, value()?)?),
+            "--height" => height = Some(parse(flag, value()?)?),
+            "--mines" => mines = Some(parse(flag, value()?)?),
+            "--seed" => board.seed = Some(parse(flag, value()?)?),
+            "--replay" => settings.replay = Some(PathBuf::from(value()?)),
+            _ => return Err(ConfigError::UnknownArgument(flag.clone())),
+        }
+        if matches!(
+            flag.as_str(),
+            "--preset" | "--width" | "--height" | "--mines"
+        ) {
+            settings.menu = false;
+        }
+    }
+
+    // 与参数顺序无关：先应用经典难度，再应用地图大小，炸弹密度按最终的地图大小计算
+    let density = match difficulty {
+        Some(Difficulty::Percent(_)) => difficulty.take(),
+        _ => None,
+    };
+    if let Some(difficulty) = difficulty {
+        board
+            .set_difficulty(difficulty)
+            .map_err(ConfigError::Board)?;
+    }
+    if let Some(width) = width {
+        board.map_size.0 = width;
+    }
+    if let Some(height) = height {
+        board.map_size.1 = height;
+    }
+    if let Some(density) = density {
+        board.set_difficulty(density).map_err(ConfigError::Board)?;
+    }
+    if let Some(mines) = mines {
+        board.bomb_count = mines;
+    }
+
+    validate(&settings.config)?;
+    Ok(settings)
+}
+
+fn read_config(path: PathBuf) -> Result<Config, ConfigError> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) => return Err(ConfigError::Io { path, error }),
+    };
+    ron::from_str(&content).map_err(|error| ConfigError::Parse { path, error })
+}
+
+// 查找某个参数的值，不检查其他参数
+fn value_of(args: &[String], flag: &str) -> Result<Option<String>, ConfigError> {
+    let Some(index) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    args.get(index + 1)
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| ConfigError::MissingValue(flag.to_string()))
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: String) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| ConfigError::InvalidValue {
+        flag: flag.to_string(),
+        value,
+    })
+}
+
+// 经典难度或者以%结尾的炸弹密度
+fn preset(name: &str) -> Result<Difficulty, ConfigError> {
+    let unknown = || ConfigError::UnknownPreset(name.to_string());
+    match name.to_lowercase().as_str() {
+        "beginner" => Ok(Difficulty::Beginner),
+        "intermediate" => Ok(Difficulty::Intermediate),
+        "expert" => Ok(Difficulty::Expert),
+        density => {
+            let percent: f32 = density
+                .strip_suffix('%')
+                .and_then(|percent| percent.parse().ok())
+                .ok_or_else(unknown)?;
+            if !(0. ..=100.).contains(&percent) {
+                return Err(unknown());
+            }
+            Ok(Difficulty::Percent(percent))
+        }
+    }
+}
+
+fn validate(config: &Config) -> Result<(), ConfigError> {
+    config.board.validate().map_err(ConfigError::Board)?;
+    if config.window.width <= 0. || config.window.height <= 0. {
+        return Err(ConfigError::Invalid(format!(
+            "window size {}x{} must be positive",
+            config.window.width, config.window.height
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn preset_then_overrides() {
+        let settings = load(args("--preset expert --mines 120 --seed 7")).unwrap();
+        let board = &settings.config.board;
+        assert_eq!(board.map_size, (30, 16));
+        assert_eq!(board.bomb_count, 120);
+        assert_eq!(board.seed, Some(7));
+        assert!(!settings.menu);
+        let settings = load(args("--mines 120 --preset expert")).unwrap();
+        assert_eq!(settings.config.board.bomb_count, 120);
+    }
+
+    #[test]
+    fn density_preset() {
+        let settings = load(args("--width 10 --height 10 --preset 25%")).unwrap();
+        assert_eq!(settings.config.board.bomb_count, 25);
+        // 密度按最终的地图大小计算，与参数顺序无关
+        let settings = load(args("--preset 25% --width 10 --height 10")).unwrap();
+        assert_eq!(settings.config.board.bomb_count, 25);
+        assert!(matches!(
+            load(args("--width 300 --height 300 --preset 80%")),
+            Err(ConfigError::Board(
+                BoardOptionsError::DensityOverflow { .. }
+            ))
+        ));
+        assert!(matches!(
+            load(args("--preset 150%")),
+            Err(ConfigError::UnknownPreset(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(matches!(
+            load(args("--width abc")),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            load(args("--mines")),
+            Err(ConfigError::MissingValue(_))
+        ));
+        assert!(matches!(
+            load(args("--preset impossible")),
+            Err(ConfigError::UnknownPreset(_))
+        ));
+        assert!(matches!(
+            load(args("--fast")),
+            Err(ConfigError::UnknownArgument(_))
+        ));
+        assert!(matches!(
+            load(args("--width 3 --height 3 --mines 9")),
+            Err(ConfigError::Board(BoardOptionsError::TooManyMines { .. }))
+        ));
+    }
+
+    #[test]
+    fn parse_config_file() {
+        let config: Config = ron::from_str(
+            "(window: (width: 400, height: 500), board: (map_size: (8, 8), bomb_count: 10))",
+        )
+        .unwrap();
+        assert_eq!(config.window.width, 400.);
+        assert_eq!(config.board.map_size, (8, 8));
+        // 没有写出的字段与Config::default()相同
+        let default = Config::default().board;
+        assert!(config.board.hud);
+        assert!(config.board.safe_start);
+        assert!(config.board.autosave);
+        assert_eq!(config.board.tile_padding, default.tile_padding);
+    }
+}
@@ -1,32 +1,90 @@
-use bevy::prelude::*;
+use bevy::{log, prelude::*};
 
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::WorldInspectorPlugin;
-use board_plugin::{resources::BoardOptions, BoardPlugin};
+use board_plugin::{
+    resources::{
+        replay::{Replay, ReplayPlayback},
+        save::SavedGame,
+    },
+    BoardPlugin,
+};
+use config::Config;
+use menu::{CustomBoard, MenuPlugin};
+
+mod config;
+mod menu;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum AppState {
+    Menu,
+    InGame,
+    Paused,
+    Out,
+}
 
 fn main() {
+    let settings = match config::load(std::env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, config::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if settings.help {
+        println!("{}", config::USAGE);
+        return;
+    }
+    let Config { window, board } = settings.config;
+    // 有自动保存的游戏时直接继续
+    let resume = board.autosave && SavedGame::default_path().is_some_and(|path| path.exists());
+    let mut menu = settings.menu && settings.replay.is_none() && !resume;
+
     let mut app = App::new();
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         window: WindowDescriptor {
             title: "Mine Sweeper!".into(),
-            width: 700.,
-            height: 800.,
+            width: window.width,
+            height: window.height,
             ..default()
         },
         ..default()
     }))
-    .insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 40,
-        tile_padding: 3.,
-        ..default()
+    .insert_resource(CustomBoard {
+        map_size: board.map_size,
+        bomb_count: board.bomb_count,
+    })
+    .insert_resource(board);
+
+    // 回放保存的录像
+    if let Some(path) = settings.replay {
+        match Replay::load(&path) {
+            Ok(replay) => {
+                log::info!("Playing replay {}", path.display());
+                app.insert_resource(replay.options.clone())
+                    .insert_resource(ReplayPlayback::new(replay));
+            }
+            Err(e) => {
+                log::error!("Failed to load replay {}: {}", path.display(), e);
+                menu = true;
+            }
+        }
+    }
+    app.add_state(if menu {
+        AppState::Menu
+    } else {
+        AppState::InGame
     });
 
     #[cfg(feature = "debug")]
     app.add_plugin(WorldInspectorPlugin::new());
 
     app.add_startup_system(camera_setup);
-    app.add_plugin(BoardPlugin);
+    app.add_plugin(MenuPlugin);
+    app.add_plugin(BoardPlugin {
+        running_state: AppState::InGame,
+    });
+    app.add_system(state_handler);
     app.run();
 }
 
@@ -34,3 +92,42 @@ fn camera_setup(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
     // .insert(UiCameraConfig { show_ui: true });
 }
+
+// Escape暂停/继续，C清除board，G重新生成board，M返回难度选择菜单
+fn state_handler(mut state: ResMut<State<AppState>>, keys: Res<Input<KeyCode>>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        let result = match state.current() {
+            AppState::InGame => {
+                log::info!("Pausing game");
+                state.push(AppState::Paused)
+            }
+            AppState::Paused => {
+                log::info!("Resuming game");
+                state.pop()
+            }
+            AppState::Menu | AppState::Out => Ok(()),
+        };
+        if let Err(e) = result {
+            log::error!("{}", e);
+        }
+    }
+    if keys.just_pressed(KeyCode::C) && state.current() == &AppState::InGame {
+        log::info!("Clearing board");
+        if let Err(e) = state.set(AppState::Out) {
+            log::error!("{}", e);
+        }
+    }
+    if keys.just_pressed(KeyCode::G) && state.current() == &AppState::Out {
+        log::info!("Generating new board");
+        if let Err(e) = state.set(AppState::InGame) {
+            log::error!("{}", e);
+        }
+    }
+    if keys.just_pressed(KeyCode::M) && matches!(state.current(), AppState::InGame | AppState::Out)
+    {
+        log::info!("Opening menu");
+        if let Err(e) = state.set(AppState::Menu) {
+            log::error!("{}", e);
+        }
+    }
+}
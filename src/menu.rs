@@ -0,0 +1,146 @@
+use bevy::{log, prelude::*};
+use board_plugin::resources::{BoardOptions, Difficulty};
+
+use crate::AppState;
+
+const MENU_FONT_SIZE: f32 = 24.;
+
+// 难度选择菜单的根节点
+#[derive(Component)]
+pub struct MenuRoot;
+
+// 选择某个难度的按钮
+#[derive(Component)]
+pub struct MenuButton(pub Difficulty);
+
+// 配置文件中的地图大小和炸弹数，选择Custom时恢复
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct CustomBoard {
+    pub map_size: (u16, u16),
+    pub bomb_count: u16,
+}
+
+// 菜单中的选项，数字键1到4依次对应
+const CHOICES: [(Difficulty, KeyCode); 4] = [
+    (Difficulty::Beginner, KeyCode::Key1),
+    (Difficulty::Intermediate, KeyCode::Key2),
+    (Difficulty::Expert, KeyCode::Key3),
+    (Difficulty::Custom, KeyCode::Key4),
+];
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(AppState::Menu).with_system(spawn_menu))
+            .add_system_set(SystemSet::on_update(AppState::Menu).with_system(choose_difficulty))
+            .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(despawn_menu));
+    }
+}
+
+fn spawn_menu(mut commands: Commands, custom: Res<CustomBoard>, assets_server: Res<AssetServer>) {
+    let text_style = TextStyle {
+        font: assets_server.load("fonts/pixeled.ttf"),
+        font_size: MENU_FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Name::new("Menu"))
+        .insert(MenuRoot)
+        .with_children(|parent| {
+            for (index, (difficulty, _)) in CHOICES.iter().enumerate() {
+                let ((width, height), mines) = difficulty
+                    .classic()
+                    .unwrap_or((custom.map_size, custom.bomb_count));
+                let label = format!(
+                    "{}. {} {}x{} / {}",
+                    index + 1,
+                    difficulty,
+                    width,
+                    height,
+                    mines
+                );
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(500.), Val::Px(70.)),
+                            margin: UiRect::all(Val::Px(10.)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::GRAY.into(),
+                        ..default()
+                    })
+                    .insert(Name::new(difficulty.to_string()))
+                    .insert(MenuButton(*difficulty))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(label, text_style.clone()));
+                    });
+            }
+        });
+}
+
+type MenuButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        &'static MenuButton,
+        &'static mut BackgroundColor,
+    ),
+    Changed<Interaction>,
+>;
+
+// 点击按钮或者按下数字键选择难度后开始游戏
+fn choose_difficulty(
+    mut buttons: MenuButtonQuery,
+    keys: Res<Input<KeyCode>>,
+    custom: Res<CustomBoard>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state: ResMut<State<AppState>>,
+) {
+    let mut chosen = CHOICES
+        .iter()
+        .find(|(_, key)| keys.just_pressed(*key))
+        .map(|(difficulty, _)| *difficulty);
+    for (interaction, button, mut color) in buttons.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => chosen = Some(button.0),
+            Interaction::Hovered => *color = Color::SILVER.into(),
+            Interaction::None => *color = Color::GRAY.into(),
+        }
+    }
+    let Some(difficulty) = chosen else {
+        return;
+    };
+
+    log::info!("Starting {} game", difficulty);
+    if difficulty == Difficulty::Custom {
+        board_options.map_size = custom.map_size;
+        board_options.bomb_count = custom.bomb_count;
+    } else if let Err(e) = board_options.set_difficulty(difficulty) {
+        log::error!("{}", e);
+        return;
+    }
+    if let Err(e) = state.set(AppState::InGame) {
+        log::error!("{}", e);
+    }
+}
+
+fn despawn_menu(mut commands: Commands, menu: Query<Entity, With<MenuRoot>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}